@@ -23,6 +23,6 @@ pub trait Controls {
 pub use first_person::{
     FirstPersonControls, FirstPersonInputs, FirstPersonSettings,
 };
-pub use flat::{FlatControls, FlatInputs, FlatSettings};
+pub use flat::{FlatControls, FlatInputs, FlatSettings, WorldBounds};
 pub use flying::{FlyingControls, FlyingInputs, FlyingSettings};
 pub use orbit::{OrbitControls, OrbitInputs, OrbitSettings};
@@ -1,5 +1,26 @@
 use super::Controls;
-use glam::{Mat4, Vec3};
+use glam::{Mat4, Quat, Vec2, Vec3};
+
+/// Region of the world the view is kept within.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WorldBounds {
+    pub left: f32,
+    pub bottom: f32,
+    pub right: f32,
+    pub top: f32,
+}
+
+impl WorldBounds {
+    pub fn new(left: f32, bottom: f32, right: f32, top: f32) -> Self {
+        Self {
+            left,
+            bottom,
+            right,
+            top,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct FlatInputs {
     /// move in this direction.
@@ -7,23 +28,84 @@ pub struct FlatInputs {
     pub right: f32,
     pub up: f32,
     pub down: f32,
+    /// A positive value zooms in, while a negative value zooms out.
+    pub zoom: f32,
+    /// Screen point in pixels that stays put while zooming. The middle of
+    /// the viewport if None.
+    pub zoom_focus: Option<[f32; 2]>,
+    /// A positive value turns the view counterclockwise.
+    pub rotate: f32,
 }
 
 #[derive(Clone, Debug)]
 pub struct FlatSettings {
+    /// The zoom to start at.
     pub zoom: f32,
+    /// The minimum zoom.
+    pub min_zoom: f32,
+    /// The maximum zoom, f32::MAX for no limit.
+    pub max_zoom: f32,
+    /// World units moved a second at a zoom of 1.0. Zoomed in it moves
+    /// slower so it covers the same distance on screen.
+    pub pan_speed: f32,
+    /// The zoom speed.
+    pub zoom_speed: f32,
+    /// Radians turned a second.
+    pub rotate_speed: f32,
+    /// Keeps the view within this region if set.
+    pub bounds: Option<WorldBounds>,
+    /// Seconds to close half the gap to where the camera is headed. 0.0
+    /// snaps straight there.
+    pub damping: f32,
+    /// Furthest the view is pushed in pixels at full trauma.
+    pub shake_offset: f32,
+    /// Furthest the view is turned in radians at full trauma.
+    pub shake_angle: f32,
+    /// Shakes a second.
+    pub shake_frequency: f32,
+    /// Trauma lost a second.
+    pub trauma_decay: f32,
 }
 
 impl Default for FlatSettings {
     fn default() -> Self {
-        Self { zoom: 1.0 }
+        Self {
+            zoom: 1.0,
+            min_zoom: 0.1,
+            max_zoom: f32::MAX,
+            pan_speed: 200.0,
+            zoom_speed: 0.1,
+            rotate_speed: 1.0,
+            bounds: None,
+            damping: 0.0,
+            shake_offset: 8.0,
+            shake_angle: (3.0_f32).to_radians(),
+            shake_frequency: 15.0,
+            trauma_decay: 1.0,
+        }
     }
 }
 
+/// 2D camera looking down at the world. The position is the world point
+/// shown in the middle of the viewport, which is the bottom left corner
+/// until a viewport is set.
 #[derive(Clone, Debug)]
 pub struct FlatControls {
     inputs: FlatInputs,
     settings: FlatSettings,
+    position: Vec2,
+    zoom: f32,
+    rotation: f32,
+    /// Where damping is moving the camera to.
+    target_position: Vec2,
+    target_zoom: f32,
+    target_rotation: f32,
+    /// Size of the view in pixels.
+    viewport: Vec2,
+    /// How hard the view shakes, from 0.0 to 1.0.
+    trauma: f32,
+    /// Seconds the shake has run for, to pick where it is at.
+    shake_time: f32,
     view: Mat4,
     eye: Vec3,
     changed: bool,
@@ -35,19 +117,168 @@ impl FlatControls {
     }
 
     pub fn new(settings: FlatSettings) -> Self {
+        let zoom = settings.zoom.clamp(settings.min_zoom, settings.max_zoom);
+
         Self {
             inputs: FlatInputs::default(),
             settings,
+            position: Vec2::ZERO,
+            zoom,
+            rotation: 0.0,
+            target_position: Vec2::ZERO,
+            target_zoom: zoom,
+            target_rotation: 0.0,
+            viewport: Vec2::ZERO,
+            trauma: 0.0,
+            shake_time: 0.0,
             view: Mat4::IDENTITY,
             eye: Vec3::ZERO,
             changed: true,
         }
     }
 
+    pub fn position(&self) -> [f32; 2] {
+        self.position.into()
+    }
+
+    pub fn rotation(&self) -> f32 {
+        self.rotation
+    }
+
+    pub fn settings(&self) -> &FlatSettings {
+        &self.settings
+    }
+
+    pub fn set_bounds(&mut self, bounds: Option<WorldBounds>) {
+        self.settings.bounds = bounds;
+        self.changed = true;
+    }
+
     pub fn set_inputs(&mut self, inputs: FlatInputs) {
         self.inputs = inputs;
         self.changed = true;
     }
+
+    /// Moves straight to position.
+    pub fn set_position(&mut self, position: [f32; 2]) {
+        self.position = position.into();
+        self.target_position = self.position;
+        self.changed = true;
+    }
+
+    /// Moves to position, easing there with damping.
+    pub fn move_to(&mut self, position: [f32; 2]) {
+        self.target_position = position.into();
+        self.changed = true;
+    }
+
+    /// Turns straight to rotation in radians.
+    pub fn set_rotation(&mut self, rotation: f32) {
+        self.rotation = rotation;
+        self.target_rotation = rotation;
+        self.changed = true;
+    }
+
+    /// Turns to rotation in radians, easing there with damping.
+    pub fn rotate_to(&mut self, rotation: f32) {
+        self.target_rotation = rotation;
+        self.changed = true;
+    }
+
+    /// Size of the view in pixels, which the position is centered in.
+    pub fn set_viewport(&mut self, size: [f32; 2]) {
+        self.viewport = size.into();
+        self.changed = true;
+    }
+
+    pub fn viewport(&self) -> [f32; 2] {
+        self.viewport.into()
+    }
+
+    /// Zooms straight to zoom.
+    pub fn set_zoom(&mut self, zoom: f32) {
+        self.zoom = self.clamp_zoom(zoom);
+        self.target_zoom = self.zoom;
+        self.changed = true;
+    }
+
+    /// Zooms to zoom, easing there with damping. The world point under
+    /// focus, a screen point in pixels, stays put.
+    pub fn zoom_at(&mut self, zoom: f32, focus: [f32; 2]) {
+        let focus = Vec2::from(focus);
+        let world = self.target_to_world(focus);
+
+        self.target_zoom = self.clamp_zoom(zoom);
+        self.target_position += world - self.target_to_world(focus);
+        self.changed = true;
+    }
+
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    /// Shakes the view. Trauma adds up to 1.0 and wears off over time, the
+    /// shake growing with the square of it so small knocks stay subtle.
+    pub fn add_trauma(&mut self, trauma: f32) {
+        self.trauma = (self.trauma + trauma).clamp(0.0, 1.0);
+        self.changed = true;
+    }
+
+    pub fn trauma(&self) -> f32 {
+        self.trauma
+    }
+
+    /// World point drawn at a screen point in pixels, leaving out any
+    /// shake.
+    pub fn screen_to_world(&self, point: [f32; 2]) -> [f32; 2] {
+        let point = Vec2::from(point) - self.viewport * 0.5;
+        let unrotated = Vec2::from_angle(-self.rotation).rotate(point);
+
+        (self.position + unrotated / self.zoom).into()
+    }
+
+    /// Screen point in pixels a world point is drawn at, leaving out any
+    /// shake.
+    pub fn world_to_screen(&self, point: [f32; 2]) -> [f32; 2] {
+        let offset = (Vec2::from(point) - self.position) * self.zoom;
+        let rotated = Vec2::from_angle(self.rotation).rotate(offset);
+
+        (rotated + self.viewport * 0.5).into()
+    }
+
+    /// Like screen_to_world but for where the camera is headed.
+    fn target_to_world(&self, point: Vec2) -> Vec2 {
+        let point = point - self.viewport * 0.5;
+        let unrotated = Vec2::from_angle(-self.target_rotation).rotate(point);
+
+        self.target_position + unrotated / self.target_zoom
+    }
+
+    fn clamp_zoom(&self, zoom: f32) -> f32 {
+        zoom.clamp(self.settings.min_zoom, self.settings.max_zoom)
+    }
+
+    /// Keeps the view at zoom within the bounds, centering it on any side
+    /// where the bounds are smaller than the view. Rotation is left out.
+    fn clamp_position(&self, position: Vec2, zoom: f32) -> Vec2 {
+        let Some(bounds) = self.settings.bounds else {
+            return position;
+        };
+
+        let half = self.viewport * 0.5 / zoom;
+        let clamp = |value: f32, low: f32, high: f32, half: f32| {
+            if high - low < half * 2.0 {
+                (low + high) * 0.5
+            } else {
+                value.clamp(low + half, high - half)
+            }
+        };
+
+        Vec2::new(
+            clamp(position.x, bounds.left, bounds.right, half.x),
+            clamp(position.y, bounds.bottom, bounds.top, half.y),
+        )
+    }
 }
 
 impl Controls for FlatControls {
@@ -55,16 +286,121 @@ impl Controls for FlatControls {
         self.eye.into()
     }
 
-    fn update(&mut self, _delta: f32) -> bool {
-        let changed = self.changed;
+    fn update(&mut self, delta: f32) -> bool {
+        let mut changed = self.changed;
+
+        let pan = Vec2::new(
+            self.inputs.right - self.inputs.left,
+            self.inputs.up - self.inputs.down,
+        );
+        if pan != Vec2::ZERO {
+            // Pan along the screen rather than the world.
+            let direction = Vec2::from_angle(-self.target_rotation).rotate(pan);
+            self.target_position +=
+                direction * self.settings.pan_speed * delta / self.target_zoom;
+
+            // Reset the input.
+            self.inputs.left = 0.0;
+            self.inputs.right = 0.0;
+            self.inputs.up = 0.0;
+            self.inputs.down = 0.0;
+            changed = true;
+        }
+
+        if self.inputs.zoom != 0.0 {
+            let zoom = self.target_zoom
+                * (self.settings.zoom_speed * self.inputs.zoom).exp();
+            let focus = self
+                .inputs
+                .zoom_focus
+                .unwrap_or((self.viewport * 0.5).into());
+            self.zoom_at(zoom, focus);
+
+            // Reset the input.
+            self.inputs.zoom = 0.0;
+            self.inputs.zoom_focus = None;
+            changed = true;
+        }
+
+        if self.inputs.rotate != 0.0 {
+            self.target_rotation +=
+                self.settings.rotate_speed * delta * self.inputs.rotate;
+
+            // Reset the input.
+            self.inputs.rotate = 0.0;
+            changed = true;
+        }
+
+        self.target_position =
+            self.clamp_position(self.target_position, self.target_zoom);
+
+        // Close the same part of the gap each second whatever the frame
+        // rate, and snap once it is too small to see.
+        let ease = if self.settings.damping > 0.0 {
+            1.0 - 0.5_f32.powf(delta / self.settings.damping)
+        } else {
+            1.0
+        };
+
+        let position = self.position.lerp(self.target_position, ease);
+        let zoom = self.zoom + (self.target_zoom - self.zoom) * ease;
+        let rotation =
+            self.rotation + (self.target_rotation - self.rotation) * ease;
+
+        let position = if position.distance(self.target_position) < 1e-3 {
+            self.target_position
+        } else {
+            self.clamp_position(position, zoom)
+        };
+        let zoom = if (zoom - self.target_zoom).abs() < 1e-4 {
+            self.target_zoom
+        } else {
+            zoom
+        };
+        let rotation = if (rotation - self.target_rotation).abs() < 1e-4 {
+            self.target_rotation
+        } else {
+            rotation
+        };
+
+        if position != self.position
+            || zoom != self.zoom
+            || rotation != self.rotation
+        {
+            self.position = position;
+            self.zoom = zoom;
+            self.rotation = rotation;
+            changed = true;
+        }
+
+        let mut shake = (Vec2::ZERO, 0.0);
+        if self.trauma > 0.0 {
+            self.shake_time += delta;
+            self.trauma =
+                (self.trauma - self.settings.trauma_decay * delta).max(0.0);
+
+            let amount = self.trauma * self.trauma;
+            let time = self.shake_time * self.settings.shake_frequency;
+            shake = (
+                Vec2::new(wobble(time, 0.0), wobble(time, 1.0))
+                    * self.settings.shake_offset
+                    * amount,
+                wobble(time, 2.0) * self.settings.shake_angle * amount,
+            );
+            changed = true;
+        } else {
+            self.shake_time = 0.0;
+        }
 
         if changed {
-            self.view = Mat4::IDENTITY
-                * Mat4::from_scale(Vec3::new(
-                    self.settings.zoom,
-                    self.settings.zoom,
-                    self.settings.zoom,
-                ));
+            let center = self.viewport * 0.5 + shake.0;
+
+            self.view = Mat4::from_scale_rotation_translation(
+                Vec3::splat(self.zoom),
+                Quat::from_rotation_z(self.rotation + shake.1),
+                center.extend(0.0),
+            ) * Mat4::from_translation(-self.position.extend(0.0));
+            self.eye = self.position.extend(0.0);
         }
 
         self.changed = false;
@@ -76,6 +412,133 @@ impl Controls for FlatControls {
     }
 
     fn scale(&self) -> f32 {
-        self.settings.zoom
+        self.zoom
+    }
+}
+
+/// Smooth back and forth motion from -1.0 to 1.0 that never quite
+/// repeats. Each seed gives a different one.
+fn wobble(time: f32, seed: f32) -> f32 {
+    let seed = seed * 12.9898;
+    ((time + seed).sin() * 0.6
+        + (time * 2.31 + seed * 1.7).sin() * 0.3
+        + (time * 4.87 + seed * 2.9).sin() * 0.1)
+        .clamp(-1.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(a: [f32; 2], b: [f32; 2]) {
+        assert!(
+            Vec2::from(a).distance(Vec2::from(b)) < 1e-3,
+            "{a:?} != {b:?}"
+        );
+    }
+
+    fn controls(settings: FlatSettings) -> FlatControls {
+        let mut controls = FlatControls::new(settings);
+        controls.set_viewport([800.0, 600.0]);
+        controls.set_position([100.0, 50.0]);
+        controls.update(0.0);
+        controls
+    }
+
+    #[test]
+    fn zoom_is_unbounded_above_by_default() {
+        let mut controls = controls(FlatSettings {
+            zoom: 40.0,
+            ..Default::default()
+        });
+        assert_eq!(controls.zoom(), 40.0);
+
+        controls.set_zoom(0.0);
+        assert_eq!(controls.zoom(), controls.settings().min_zoom);
+    }
+
+    #[test]
+    fn zoom_at_keeps_the_focus_still() {
+        let mut controls = controls(FlatSettings::default());
+        controls.set_rotation(0.5);
+        controls.update(0.0);
+
+        let focus = [650.0, 120.0];
+        let world = controls.screen_to_world(focus);
+        controls.zoom_at(3.0, focus);
+        controls.update(0.0);
+
+        assert_eq!(controls.zoom(), 3.0);
+        assert_near(controls.screen_to_world(focus), world);
+        assert_near(controls.world_to_screen(world), focus);
+    }
+
+    #[test]
+    fn bounds_keep_the_view_inside() {
+        let mut controls = controls(FlatSettings {
+            bounds: Some(WorldBounds::new(0.0, 0.0, 1000.0, 400.0)),
+            ..Default::default()
+        });
+
+        // 800 wide fits so it is clamped, 600 tall does not so it centers.
+        controls.move_to([-500.0, 900.0]);
+        controls.update(0.0);
+        assert_eq!(controls.position(), [400.0, 200.0]);
+
+        controls.move_to([2000.0, -100.0]);
+        controls.update(0.0);
+        assert_eq!(controls.position(), [600.0, 200.0]);
+
+        // Zoomed in the view is smaller so it can go further.
+        controls.set_zoom(2.0);
+        controls.move_to([2000.0, -100.0]);
+        controls.update(0.0);
+        assert_eq!(controls.position(), [800.0, 150.0]);
+    }
+
+    #[test]
+    fn damping_closes_half_the_gap_each_period() {
+        let mut controls = controls(FlatSettings {
+            damping: 0.25,
+            ..Default::default()
+        });
+
+        controls.move_to([500.0, 50.0]);
+        controls.update(0.25);
+        assert_near(controls.position(), [300.0, 50.0]);
+        controls.update(0.25);
+        assert_near(controls.position(), [400.0, 50.0]);
+
+        for _ in 0..100 {
+            controls.update(0.25);
+        }
+        assert_eq!(controls.position(), [500.0, 50.0]);
+        assert!(!controls.update(0.25));
+    }
+
+    #[test]
+    fn trauma_wears_off() {
+        let mut controls = controls(FlatSettings {
+            trauma_decay: 0.5,
+            ..Default::default()
+        });
+        let still = controls.view;
+
+        controls.add_trauma(0.75);
+        controls.add_trauma(0.75);
+        assert_eq!(controls.trauma(), 1.0);
+
+        assert!(controls.update(0.5));
+        assert_eq!(controls.trauma(), 0.75);
+        assert_ne!(controls.view, still);
+        // Shake leaves the camera where it is.
+        assert_eq!(controls.position(), [100.0, 50.0]);
+
+        for _ in 0..3 {
+            controls.update(0.5);
+        }
+        assert_eq!(controls.trauma(), 0.0);
+        controls.update(0.5);
+        assert_eq!(controls.view, still);
     }
 }
@@ -0,0 +1,115 @@
+use graphics::*;
+use serde::{Deserialize, Serialize};
+
+use crate::theme::Palette;
+
+/// Text sizes that can be picked from the options menu.
+pub const TEXT_SCALES: [f32; 3] = [1.0, 1.25, 1.5];
+
+/// Palettes for the hit and miss markers. The colour-blind ones use colours
+/// that stay apart under that kind of colour blindness.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorMode {
+    Normal,
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+}
+
+impl ColorMode {
+    pub const ALL: [ColorMode; 4] = [
+        ColorMode::Normal,
+        ColorMode::Deuteranopia,
+        ColorMode::Protanopia,
+        ColorMode::Tritanopia,
+    ];
+
+    /// Locale message key of the modes name.
+    pub fn message_key(&self) -> &'static str {
+        match self {
+            ColorMode::Normal => "color-normal",
+            ColorMode::Deuteranopia => "color-deuteranopia",
+            ColorMode::Protanopia => "color-protanopia",
+            ColorMode::Tritanopia => "color-tritanopia",
+        }
+    }
+
+    pub fn markers(&self) -> MarkerColors {
+        match self {
+            ColorMode::Normal => MarkerColors {
+                hit_tint: [255, 255, 255, 255],
+                miss_tint: [255, 255, 255, 255],
+                hit: [220, 40, 40, 255],
+                miss: [255, 255, 255, 255],
+                target: [255, 220, 0, 255],
+            },
+            // Orange and blue stay apart when red and green do not.
+            ColorMode::Deuteranopia | ColorMode::Protanopia => MarkerColors {
+                hit_tint: [230, 159, 0, 255],
+                miss_tint: [86, 180, 233, 255],
+                hit: [230, 159, 0, 255],
+                miss: [86, 180, 233, 255],
+                target: [255, 255, 255, 255],
+            },
+            // Red and teal stay apart when blue and yellow do not.
+            ColorMode::Tritanopia => MarkerColors {
+                hit_tint: [213, 94, 0, 255],
+                miss_tint: [0, 158, 115, 255],
+                hit: [213, 94, 0, 255],
+                miss: [0, 158, 115, 255],
+                target: [255, 255, 255, 255],
+            },
+        }
+    }
+}
+
+/// Colours used to tell hits, misses and whose board is being fired at apart.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MarkerColors {
+    /// Tint over the hit icons art.
+    pub hit_tint: [u8; 4],
+    /// Tint over the miss icons art.
+    pub miss_tint: [u8; 4],
+    /// High contrast cross drawn over hits.
+    pub hit: [u8; 4],
+    /// High contrast ring drawn over misses.
+    pub miss: [u8; 4],
+    /// High contrast outline around the board being fired at.
+    pub target: [u8; 4],
+}
+
+impl MarkerColors {
+    pub fn tint(&self, hit: bool) -> Color {
+        Palette::color(if hit { self.hit_tint } else { self.miss_tint })
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Accessibility {
+    pub color_mode: ColorMode,
+    /// Outlines hits, misses and the board being fired at with Mesh2D shapes.
+    pub high_contrast: bool,
+    /// Multiplies the font size of all Text.
+    pub text_scale: f32,
+    /// Skips hit, splash and sinking effects.
+    pub reduced_motion: bool,
+}
+
+impl Default for Accessibility {
+    fn default() -> Self {
+        Self {
+            color_mode: ColorMode::Normal,
+            high_contrast: false,
+            text_scale: TEXT_SCALES[0],
+            reduced_motion: false,
+        }
+    }
+}
+
+impl Accessibility {
+    pub fn markers(&self) -> MarkerColors {
+        self.color_mode.markers()
+    }
+}
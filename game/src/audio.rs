@@ -0,0 +1,239 @@
+mod backend;
+mod cues;
+mod mixer;
+mod music;
+
+pub use backend::*;
+pub use cues::*;
+pub use mixer::*;
+pub use music::*;
+
+use graphics::*;
+
+use crate::layout::Rect;
+
+/// Turns game events into sound cues and keeps the music playing. All the
+/// sound goes out through the backend so it can be swapped for one that
+/// records instead.
+pub struct Audio<B: AudioBackend> {
+    backend: B,
+    pub mixer: Mixer,
+    pub cues: CueMap,
+    pub music: MusicPlayer,
+    /// Area sounds are panned across, left edge to right edge.
+    listener: Rect,
+}
+
+impl<B: AudioBackend> Audio<B> {
+    pub fn new(backend: B, cues: CueMap, listener: Rect) -> Self {
+        Self {
+            backend,
+            mixer: Mixer::default(),
+            music: MusicPlayer::new(cues.crossfade),
+            cues,
+            listener,
+        }
+    }
+
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    pub fn backend_mut(&mut self) -> &mut B {
+        &mut self.backend
+    }
+
+    /// Sets the area sounds are panned across. Usually the panel.
+    pub fn set_listener(&mut self, listener: Rect) {
+        self.listener = listener;
+    }
+
+    /// Plays the cue mapped to the event. Events without a cue are ignored.
+    pub fn emit(&mut self, event: AudioEvent) {
+        let Some(cue) = self.cues.events.get(&event.kind) else {
+            return;
+        };
+
+        let volume = cue.volume * self.mixer.volume(cue.category);
+        if volume <= 0.0 {
+            return;
+        }
+
+        let pan = event.pos.map_or(0.0, |pos| self.pan(pos));
+        self.backend.submit(AudioCommand::Play {
+            sound: cue.sound.clone(),
+            volume,
+            pan,
+        });
+    }
+
+    /// Crossfades over to the scenes music track if it has one.
+    pub fn set_scene(&mut self, scene: Scene) {
+        let track = self.cues.music.get(&scene).cloned();
+        self.music.play(track, &self.mixer, &mut self.backend);
+    }
+
+    /// Moves the crossfade along. delta is the seconds since the last call.
+    pub fn update(&mut self, delta: f32) {
+        self.music.update(delta, &self.mixer, &mut self.backend);
+    }
+
+    /// -1.0 is fully left and 1.0 fully right of the listener.
+    pub fn pan(&self, pos: Vec2) -> f32 {
+        let half_width = self.listener.size.x * 0.5;
+        if half_width <= 0.0 {
+            return 0.0;
+        }
+
+        let center = self.listener.pos.x + half_width;
+        ((pos.x - center) / half_width).clamp(-1.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cue(sound: &str, category: SoundCategory) -> SoundCue {
+        SoundCue {
+            sound: sound.to_owned(),
+            category,
+            volume: 1.0,
+        }
+    }
+
+    /// Panel 400 wide so a board on either half pans halfway over.
+    fn audio() -> Audio<RecordingBackend> {
+        let mut cues = CueMap {
+            crossfade: 2.0,
+            ..Default::default()
+        };
+        for (kind, sound) in [
+            (AudioEventKind::ShotFired, "shot.ogg"),
+            (AudioEventKind::Splash, "splash.ogg"),
+            (AudioEventKind::Hit, "hit.ogg"),
+            (AudioEventKind::ShipSunk, "sunk.ogg"),
+        ] {
+            cues.events.insert(kind, cue(sound, SoundCategory::Effects));
+        }
+        cues.events.insert(
+            AudioEventKind::Victory,
+            cue("victory.ogg", SoundCategory::Interface),
+        );
+        cues.music.insert(Scene::Battle, "battle.ogg".to_owned());
+        cues.music.insert(Scene::Victory, "fanfare.ogg".to_owned());
+
+        Audio::new(
+            RecordingBackend::default(),
+            cues,
+            Rect::new(Vec2::new(100.0, 0.0), Vec2::new(400.0, 300.0)),
+        )
+    }
+
+    fn play(sound: &str, volume: f32, pan: f32) -> AudioCommand {
+        AudioCommand::Play {
+            sound: sound.to_owned(),
+            volume,
+            pan,
+        }
+    }
+
+    fn music(track: &str, volume: f32) -> AudioCommand {
+        AudioCommand::SetMusicVolume {
+            track: track.to_owned(),
+            volume,
+        }
+    }
+
+    #[test]
+    fn events_play_their_cues_mixed_and_panned() {
+        let mut audio = audio();
+        audio.mixer.master = 0.5;
+        audio.mixer.set_volume(SoundCategory::Effects, 0.5);
+
+        // Shots on the left board, then a miss, hit and sink on the right one.
+        audio.emit(AudioEvent::at(
+            AudioEventKind::ShotFired,
+            Vec2::new(200.0, 50.0),
+        ));
+        audio.emit(AudioEvent::at(
+            AudioEventKind::Splash,
+            Vec2::new(400.0, 50.0),
+        ));
+        audio.emit(AudioEvent::at(AudioEventKind::Hit, Vec2::new(300.0, 50.0)));
+        audio.emit(AudioEvent::at(
+            AudioEventKind::ShipSunk,
+            Vec2::new(900.0, 50.0),
+        ));
+        audio.emit(AudioEvent::new(AudioEventKind::Victory));
+
+        assert_eq!(
+            audio.backend_mut().take(),
+            [
+                play("shot.ogg", 0.25, -0.5),
+                play("splash.ogg", 0.25, 0.5),
+                play("hit.ogg", 0.25, 0.0),
+                play("sunk.ogg", 0.25, 1.0),
+                play("victory.ogg", 0.5, 0.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn silent_categories_play_nothing() {
+        let mut audio = audio();
+        audio.mixer.set_volume(SoundCategory::Effects, 0.0);
+        audio.emit(AudioEvent::new(AudioEventKind::Hit));
+        audio.emit(AudioEvent::new(AudioEventKind::Victory));
+        assert_eq!(audio.backend_mut().take(), [play("victory.ogg", 1.0, 0.0)]);
+
+        audio.mixer.muted = true;
+        audio.emit(AudioEvent::new(AudioEventKind::Victory));
+        assert!(audio.backend().commands.is_empty());
+    }
+
+    #[test]
+    fn scenes_crossfade_their_music() {
+        let mut audio = audio();
+        audio.mixer.set_volume(SoundCategory::Music, 0.5);
+
+        audio.set_scene(Scene::Battle);
+        audio.update(1.0);
+        audio.update(1.0);
+        // Already playing so nothing changes.
+        audio.set_scene(Scene::Battle);
+        audio.update(1.0);
+        assert_eq!(
+            audio.backend_mut().take(),
+            [
+                AudioCommand::PlayMusic {
+                    track: "battle.ogg".to_owned(),
+                    volume: 0.0,
+                },
+                music("battle.ogg", 0.25),
+                music("battle.ogg", 0.5),
+            ]
+        );
+
+        audio.set_scene(Scene::Victory);
+        audio.update(1.0);
+        audio.update(1.0);
+        audio.update(1.0);
+        assert_eq!(
+            audio.backend_mut().take(),
+            [
+                AudioCommand::PlayMusic {
+                    track: "fanfare.ogg".to_owned(),
+                    volume: 0.0,
+                },
+                music("battle.ogg", 0.25),
+                music("fanfare.ogg", 0.25),
+                AudioCommand::StopMusic {
+                    track: "battle.ogg".to_owned(),
+                },
+                music("fanfare.ogg", 0.5),
+            ]
+        );
+        assert_eq!(audio.music.current(), Some("fanfare.ogg"));
+    }
+}
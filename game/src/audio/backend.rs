@@ -0,0 +1,54 @@
+/// Everything the game asks of the sound output.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AudioCommand {
+    /// Plays a sound once. pan goes from -1.0 left to 1.0 right.
+    Play {
+        sound: String,
+        volume: f32,
+        pan: f32,
+    },
+    /// Starts a looping music track.
+    PlayMusic {
+        track: String,
+        volume: f32,
+    },
+    SetMusicVolume {
+        track: String,
+        volume: f32,
+    },
+    StopMusic {
+        track: String,
+    },
+}
+
+/// Sound output. Implement this to play the commands through a real device.
+pub trait AudioBackend {
+    fn submit(&mut self, command: AudioCommand);
+}
+
+/// Drops every command. Used when there is no audio device to play on.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct NullBackend;
+
+impl AudioBackend for NullBackend {
+    fn submit(&mut self, _command: AudioCommand) {}
+}
+
+/// Keeps every command in order so what would have played can be checked.
+#[derive(Clone, Debug, Default)]
+pub struct RecordingBackend {
+    pub commands: Vec<AudioCommand>,
+}
+
+impl RecordingBackend {
+    /// Returns the commands recorded so far and starts over.
+    pub fn take(&mut self) -> Vec<AudioCommand> {
+        std::mem::take(&mut self.commands)
+    }
+}
+
+impl AudioBackend for RecordingBackend {
+    fn submit(&mut self, command: AudioCommand) {
+        self.commands.push(command);
+    }
+}
@@ -0,0 +1,77 @@
+use graphics::*;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::Path};
+
+use super::SoundCategory;
+use crate::func::load_toml;
+
+/// Things that happen in game that can make a sound.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioEventKind {
+    ShotFired,
+    Splash,
+    Hit,
+    ShipSunk,
+    Victory,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AudioEvent {
+    pub kind: AudioEventKind,
+    /// Where it happened in sprite units, used for panning.
+    pub pos: Option<Vec2>,
+}
+
+impl AudioEvent {
+    pub fn new(kind: AudioEventKind) -> Self {
+        Self { kind, pos: None }
+    }
+
+    pub fn at(kind: AudioEventKind, pos: Vec2) -> Self {
+        Self {
+            kind,
+            pos: Some(pos),
+        }
+    }
+}
+
+/// Parts of the game that each have their own music.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Scene {
+    Battle,
+    Victory,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SoundCue {
+    /// Sound file handed to the backend.
+    pub sound: String,
+    pub category: SoundCategory,
+    /// Volume of this cue before the mixer is applied.
+    #[serde(default = "full_volume")]
+    pub volume: f32,
+}
+
+fn full_volume() -> f32 {
+    1.0
+}
+
+/// Which sound each event plays and which music each scene plays.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CueMap {
+    /// Seconds music takes to fade over to the next track.
+    #[serde(default)]
+    pub crossfade: f32,
+    #[serde(default)]
+    pub events: HashMap<AudioEventKind, SoundCue>,
+    #[serde(default)]
+    pub music: HashMap<Scene, String>,
+}
+
+impl CueMap {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AscendingError> {
+        load_toml(path)
+    }
+}
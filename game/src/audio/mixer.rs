@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SoundCategory {
+    Effects,
+    Music,
+    Interface,
+}
+
+/// Volumes from 0.0 to 1.0 for everything and for each category.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Mixer {
+    pub master: f32,
+    pub effects: f32,
+    pub music: f32,
+    pub interface: f32,
+    pub muted: bool,
+}
+
+impl Default for Mixer {
+    fn default() -> Self {
+        Self {
+            master: 1.0,
+            effects: 1.0,
+            music: 1.0,
+            interface: 1.0,
+            muted: false,
+        }
+    }
+}
+
+impl Mixer {
+    /// Final volume of the category with master and mute applied.
+    pub fn volume(&self, category: SoundCategory) -> f32 {
+        if self.muted {
+            return 0.0;
+        }
+
+        let volume = match category {
+            SoundCategory::Effects => self.effects,
+            SoundCategory::Music => self.music,
+            SoundCategory::Interface => self.interface,
+        };
+
+        self.master * volume
+    }
+
+    pub fn set_volume(&mut self, category: SoundCategory, volume: f32) {
+        let volume = volume.clamp(0.0, 1.0);
+
+        match category {
+            SoundCategory::Effects => self.effects = volume,
+            SoundCategory::Music => self.music = volume,
+            SoundCategory::Interface => self.interface = volume,
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use super::{AudioBackend, AudioCommand, Mixer, SoundCategory};
+
+/// A track that is fading towards its target volume.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MusicTrack {
+    pub track: String,
+    /// Fade volume from 0.0 to 1.0 before the mixer is applied.
+    pub volume: f32,
+    pub target: f32,
+}
+
+/// Crossfades from one looping music track to the next.
+#[derive(Clone, Debug)]
+pub struct MusicPlayer {
+    /// Seconds a fade in or out takes. 0.0 swaps tracks right away.
+    pub fade_time: f32,
+    /// The last track is the one playing, any before it are fading out.
+    pub tracks: Vec<MusicTrack>,
+}
+
+impl MusicPlayer {
+    pub fn new(fade_time: f32) -> Self {
+        Self {
+            fade_time,
+            tracks: Vec::new(),
+        }
+    }
+
+    pub fn current(&self) -> Option<&str> {
+        self.tracks
+            .last()
+            .filter(|track| track.target > 0.0)
+            .map(|track| track.track.as_str())
+    }
+
+    /// Fades out what is playing and fades in track. None fades to silence.
+    pub fn play(
+        &mut self,
+        track: Option<String>,
+        mixer: &Mixer,
+        backend: &mut dyn AudioBackend,
+    ) {
+        if self.current() == track.as_deref() {
+            return;
+        }
+
+        self.tracks.iter_mut().for_each(|track| track.target = 0.0);
+
+        if let Some(track) = track {
+            // Pick it back up if it was still fading out.
+            if let Some(index) =
+                self.tracks.iter().position(|music| music.track == track)
+            {
+                let mut music = self.tracks.remove(index);
+                music.target = 1.0;
+                self.tracks.push(music);
+            } else {
+                backend.submit(AudioCommand::PlayMusic {
+                    track: track.clone(),
+                    volume: 0.0,
+                });
+                self.tracks.push(MusicTrack {
+                    track,
+                    volume: 0.0,
+                    target: 1.0,
+                });
+            }
+        }
+
+        // Nothing to fade so swap over right away.
+        if self.fade_time <= 0.0 {
+            self.update(0.0, mixer, backend);
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        delta: f32,
+        mixer: &Mixer,
+        backend: &mut dyn AudioBackend,
+    ) {
+        let step = if self.fade_time > 0.0 {
+            delta / self.fade_time
+        } else {
+            1.0
+        };
+        let mix = mixer.volume(SoundCategory::Music);
+
+        self.tracks.retain_mut(|music| {
+            if music.volume == music.target {
+                return true;
+            }
+
+            music.volume = if music.volume < music.target {
+                (music.volume + step).min(music.target)
+            } else {
+                (music.volume - step).max(music.target)
+            };
+
+            if music.volume <= 0.0 && music.target <= 0.0 {
+                backend.submit(AudioCommand::StopMusic {
+                    track: music.track.clone(),
+                });
+                return false;
+            }
+
+            backend.submit(AudioCommand::SetMusicVolume {
+                track: music.track.clone(),
+                volume: music.volume * mix,
+            });
+            true
+        });
+    }
+
+    /// Sends the volumes again, such as after the mixer changed.
+    pub fn refresh(&self, mixer: &Mixer, backend: &mut dyn AudioBackend) {
+        let mix = mixer.volume(SoundCategory::Music);
+
+        for music in &self.tracks {
+            backend.submit(AudioCommand::SetMusicVolume {
+                track: music.track.clone(),
+                volume: music.volume * mix,
+            });
+        }
+    }
+}
@@ -1,24 +1,35 @@
 mod anim_icon;
+mod outline;
 
 pub use anim_icon::*;
+pub use outline::*;
 
 use graphics::{ *};
 use rand::Rng;
-use cosmic_text::{Attrs, Metrics};
+use serde::{Deserialize, Serialize};
+use cosmic_text::Attrs;
 use std::collections::HashSet;
+use hecs::{Entity, World};
 use winit::dpi::PhysicalSize;
 use crate::func::*;
 use crate::TextureAllocation;
+use crate::ecs::{move_board_entities, tint_markers, BoardCell, EffectKind, Marker, Ship, Sprite, SpriteTexture, Transform};
+use crate::audio::{Audio, AudioBackend, AudioEvent, AudioEventKind};
+use crate::accessibility::{Accessibility, ColorMode, MarkerColors};
+use crate::history::{Shot, ShotOutcome, UNKNOWN_SHIP};
+use crate::layout::ScreenLayout;
+use crate::locale::Locale;
+use crate::theme::{font_attrs, Palette, Theme};
 use crate::{
     GUI_RESULT_ORDER,
     GUI_SHADE_ORDER,
     BOARD_SIZE,
     SHIP_ORDER,
     ICON_ORDER,
-    SCREEN_ZOOM,
+    TILE_SIZE,
 };
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum BoardType {
     None,
     Ship(i32),
@@ -26,30 +37,23 @@ pub enum BoardType {
     Missed,
 }
 
-pub struct Ship {
-    pub sprite: Image,
-    pub parts: i32,
-    pub index: i32,
-    pub visible: bool,
+/// What the status text is showing. Kept instead of the string so it can be
+/// formatted again when the Locale changes.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Status {
+    None,
+    Turn(i32),
+    Winner { player: i32, ships_left: i32 },
 }
 
-impl Ship {
-    pub fn new(resource: &TextureAllocation, renderer: &mut GpuRenderer, index: i32, parts: i32) -> Self {
-        Self {
-            sprite: Image::new(Some(resource.ship_texture), renderer, 1),
-            parts,
-            index,
-            visible: false,
-        }
-    }
-
-    pub fn damage_ship(&mut self) -> bool {
-        self.parts -= 1;
-        if self.parts <= 0 {
-            self.visible = true;
-            true
-        } else {
-            false
+impl Status {
+    pub fn message(&self, locale: &Locale) -> String {
+        match *self {
+            Status::None => String::new(),
+            Status::Turn(player) => locale.text("status-turn", &[("player", &player)]),
+            Status::Winner { player, ships_left } => {
+                locale.plural("status-winner", ships_left as i64, &[("player", &player)])
+            }
         }
     }
 }
@@ -64,10 +68,13 @@ pub struct GameBoard {
     pub status_text: Text,
     pub ship_counter: [[Text; 4]; 2],
     pub ship_counter_data: [[i32; 4]; 2],
+    /// Font family from the Theme if it has one.
+    pub font_family: Option<String>,
+    pub status: Status,
 }
 
 impl GameBoard {
-    pub fn new(resource: &TextureAllocation, renderer: &mut GpuRenderer, scale: &f64) -> Self {
+    pub fn new(resource: &TextureAllocation, renderer: &mut GpuRenderer, layout: &ScreenLayout, theme: &Theme, locale: &Locale) -> Self {
         let mut rng = rand::thread_rng();
         let mut result = Self {
             got_winner: false,
@@ -85,32 +92,16 @@ impl GameBoard {
                 Image::new(Some(resource.white_texture), renderer, 1),
             ],
             status_text: Text::new(renderer,
-                            Some(Metrics::new(16.0, 16.0).scale(*scale as f32)),
-                            Vec3::new(490.0, 4.0, 0.0),
+                            Some(layout.font_metrics()),
+                            Vec3::new(0.0, 0.0, 0.0),
                             Vec2::new(200.0, 16.0)),
             ship_counter: [
-                [
-                    Text::new(renderer,Some(Metrics::new(16.0, 16.0).scale(*scale as f32)), 
-                                        Vec3::new(46.0 * SCREEN_ZOOM, 326.0 * SCREEN_ZOOM, 0.0), Vec2::new(32.0, 16.0)),
-                    Text::new(renderer,Some(Metrics::new(16.0, 16.0).scale(*scale as f32)), 
-                                        Vec3::new(46.0 * SCREEN_ZOOM, 301.0 * SCREEN_ZOOM, 0.0), Vec2::new(32.0, 16.0)),
-                    Text::new(renderer,Some(Metrics::new(16.0, 16.0).scale(*scale as f32)), 
-                                        Vec3::new(156.0 * SCREEN_ZOOM, 326.0 * SCREEN_ZOOM, 0.0), Vec2::new(32.0, 16.0)),
-                    Text::new(renderer,Some(Metrics::new(16.0, 16.0).scale(*scale as f32)), 
-                                        Vec3::new(156.0 * SCREEN_ZOOM, 301.0 * SCREEN_ZOOM, 0.0), Vec2::new(32.0, 16.0)),                        
-                ],
-                [
-                    Text::new(renderer,Some(Metrics::new(16.0, 16.0).scale(*scale as f32)), 
-                                        Vec3::new(315.0 * SCREEN_ZOOM, 326.0 * SCREEN_ZOOM, 0.0), Vec2::new(32.0, 16.0)),
-                    Text::new(renderer,Some(Metrics::new(16.0, 16.0).scale(*scale as f32)), 
-                                        Vec3::new(315.0 * SCREEN_ZOOM, 301.0 * SCREEN_ZOOM, 0.0), Vec2::new(32.0, 16.0)),
-                    Text::new(renderer,Some(Metrics::new(16.0, 16.0).scale(*scale as f32)), 
-                                        Vec3::new(425.0 * SCREEN_ZOOM, 326.0 * SCREEN_ZOOM, 0.0), Vec2::new(32.0, 16.0)),
-                    Text::new(renderer,Some(Metrics::new(16.0, 16.0).scale(*scale as f32)), 
-                                        Vec3::new(425.0 * SCREEN_ZOOM, 301.0 * SCREEN_ZOOM, 0.0), Vec2::new(32.0, 16.0)),                        
-                ],
+                [(); 4].map(|_| Text::new(renderer, Some(layout.font_metrics()), Vec3::new(0.0, 0.0, 0.0), Vec2::new(32.0, 16.0))),
+                [(); 4].map(|_| Text::new(renderer, Some(layout.font_metrics()), Vec3::new(0.0, 0.0, 0.0), Vec2::new(32.0, 16.0))),
             ],
             ship_counter_data: [[0,0,0,0],[0,0,0,0]],
+            font_family: None,
+            status: Status::Turn(1),
         };
 
         result.ship_counter_data = [
@@ -118,111 +109,144 @@ impl GameBoard {
             result.size_count.clone(),
         ];
 
-        result.status_text.set_buffer_size(renderer, renderer.size().width as i32, renderer.size().height as i32)
-            .set_bounds(Some(Bounds::new(348.0, 0.0, 746.0, 20.0)))
-            .set_default_color(Color::rgba(185, 185, 185, 255));
-
-        result.ship_counter[0][0].set_buffer_size(renderer, renderer.size().width as i32, renderer.size().height as i32)
-            .set_bounds(Some(Bounds::new(34.0 * SCREEN_ZOOM, 326.0 * SCREEN_ZOOM, 61.0 * SCREEN_ZOOM, 341.0 * SCREEN_ZOOM)))
-            .set_default_color(Color::rgba(185, 185, 185, 255));
-        result.ship_counter[0][1].set_buffer_size(renderer, renderer.size().width as i32, renderer.size().height as i32)
-            .set_bounds(Some(Bounds::new(34.0 * SCREEN_ZOOM, 301.0 * SCREEN_ZOOM, 61.0 * SCREEN_ZOOM, 316.0 * SCREEN_ZOOM)))
-            .set_default_color(Color::rgba(185, 185, 185, 255));
-        result.ship_counter[0][2].set_buffer_size(renderer, renderer.size().width as i32, renderer.size().height as i32)
-            .set_bounds(Some(Bounds::new(144.0 * SCREEN_ZOOM, 326.0 * SCREEN_ZOOM, 171.0 * SCREEN_ZOOM, 341.0 * SCREEN_ZOOM)))
-            .set_default_color(Color::rgba(185, 185, 185, 255));
-        result.ship_counter[0][3].set_buffer_size(renderer, renderer.size().width as i32, renderer.size().height as i32)
-            .set_bounds(Some(Bounds::new(144.0 * SCREEN_ZOOM, 301.0 * SCREEN_ZOOM, 171.0 * SCREEN_ZOOM, 316.0 * SCREEN_ZOOM)))
-            .set_default_color(Color::rgba(185, 185, 185, 255));
-
-        result.ship_counter[1][0].set_buffer_size(renderer, renderer.size().width as i32, renderer.size().height as i32)
-            .set_bounds(Some(Bounds::new(303.0 * SCREEN_ZOOM, 326.0 * SCREEN_ZOOM, 330.0 * SCREEN_ZOOM, 341.0 * SCREEN_ZOOM)))
-            .set_default_color(Color::rgba(185, 185, 185, 255));
-        result.ship_counter[1][1].set_buffer_size(renderer, renderer.size().width as i32, renderer.size().height as i32)
-            .set_bounds(Some(Bounds::new(303.0 * SCREEN_ZOOM, 301.0 * SCREEN_ZOOM, 330.0 * SCREEN_ZOOM, 316.0 * SCREEN_ZOOM)))
-            .set_default_color(Color::rgba(185, 185, 185, 255));
-        result.ship_counter[1][2].set_buffer_size(renderer, renderer.size().width as i32, renderer.size().height as i32)
-            .set_bounds(Some(Bounds::new(413.0 * SCREEN_ZOOM, 326.0 * SCREEN_ZOOM, 440.0 * SCREEN_ZOOM, 341.0 * SCREEN_ZOOM)))
-            .set_default_color(Color::rgba(185, 185, 185, 255));
-        result.ship_counter[1][3].set_buffer_size(renderer, renderer.size().width as i32, renderer.size().height as i32)
-            .set_bounds(Some(Bounds::new(413.0 * SCREEN_ZOOM, 301.0 * SCREEN_ZOOM, 440.0 * SCREEN_ZOOM, 316.0 * SCREEN_ZOOM)))
-            .set_default_color(Color::rgba(185, 185, 185, 255));
-
-        for x in 0..=1 {
-            for y in 0..=3 {
-                result.ship_counter[x][y].set_text(renderer, "0", Attrs::new());
-            }
-        }
-
-        result.status_text.set_text(renderer, "PLAYER 1 TURN", Attrs::new());
-
-        result.win_image.pos = Vec3::new(0.0, 0.0, GUI_RESULT_ORDER);
         result.win_image.hw = Vec2::new(240.0, 44.0);
         result.win_image.uv = Vec4::new(0.0, 0.0, 240.0, 44.0);
         result.win_image.color = Color::rgba(255, 255, 255, 255);
-        result.lose_image.pos = Vec3::new(0.0, 0.0, GUI_RESULT_ORDER);
         result.lose_image.hw = Vec2::new(240.0, 44.0);
         result.lose_image.uv = Vec4::new(0.0, 44.0, 240.0, 44.0);
         result.lose_image.color = Color::rgba(255, 255, 255, 255);
 
-        result.board_shade[0].pos = Vec3::new(27.0, 11.0, GUI_SHADE_ORDER);
-        result.board_shade[1].pos = Vec3::new(297.0, 11.0, GUI_SHADE_ORDER);
         for i in 0..=1 {
-            result.board_shade[i].hw = Vec2::new(240.0, 240.0);
             result.board_shade[i].uv = Vec4::new(0.0, 0.0, 16.0, 16.0);
-            result.board_shade[i].color = Color::rgba(0, 0, 0, 150);
         }
 
+        result.set_theme(theme, resource, locale, renderer);
+        result.set_layout(layout, renderer);
         result
     }
 
-    pub fn set_winner(&mut self, index: i32) {
-        if index > 0 {
-            self.win_image.pos = Vec3::new(296.0, 110.0, GUI_RESULT_ORDER);
-            self.lose_image.pos = Vec3::new(27.0, 110.0, GUI_RESULT_ORDER);
-        } else {
-            self.win_image.pos = Vec3::new(27.0, 110.0, GUI_RESULT_ORDER);
-            self.lose_image.pos = Vec3::new(296.0, 110.0, GUI_RESULT_ORDER);
-        }
+    /// Swaps over to the Themes textures, colors and font.
+    pub fn set_theme(&mut self, theme: &Theme, resource: &TextureAllocation, locale: &Locale, renderer: &mut GpuRenderer) {
+        self.font_family = theme.font_family();
+
+        self.win_image.texture = Some(resource.result_texture);
         self.win_image.changed = true;
+        self.lose_image.texture = Some(resource.result_texture);
         self.lose_image.changed = true;
+
+        for i in 0..=1 {
+            self.board_shade[i].texture = Some(resource.white_texture);
+            self.board_shade[i].color = Palette::color(theme.palette.shade);
+            self.board_shade[i].changed = true;
+        }
+
+        let color = Palette::color(theme.palette.text);
+        self.status_text.set_default_color(color);
+        for x in 0..=1 {
+            for y in 0..=3 {
+                self.ship_counter[x][y].set_default_color(color);
+            }
+        }
+
+        // Reshape the text so the new font gets used.
+        self.set_locale(locale, renderer);
+        for x in 0..=1 {
+            let ship_count = self.ship_counter_data[x];
+            self.update_ship_counter(&ship_count, renderer, x);
+        }
+    }
+
+    pub fn attrs(&self) -> Attrs<'_> {
+        font_attrs(&self.font_family)
+    }
+
+    /// Moves all the GUI parts into the positions given by the layout.
+    pub fn set_layout(&mut self, layout: &ScreenLayout, renderer: &mut GpuRenderer) {
+        layout.place_text(&mut self.status_text, &layout.status_text, renderer);
+
+        for x in 0..=1 {
+            for y in 0..=3 {
+                layout.place_text(&mut self.ship_counter[x][y], &layout.ship_counter[x][y], renderer);
+            }
+
+            let board = layout.boards[x];
+            self.board_shade[x].pos = Vec3::new(board.pos.x, board.pos.y, GUI_SHADE_ORDER);
+            self.board_shade[x].hw = board.size;
+            self.board_shade[x].changed = true;
+        }
+
+        self.set_result_pos(layout);
+    }
+
+    pub fn set_winner(&mut self, index: i32, layout: &ScreenLayout) {
+        self.current_turn = index;
         self.got_winner = true;
+        self.set_result_pos(layout);
+    }
+
+    fn set_result_pos(&mut self, layout: &ScreenLayout) {
+        let (win, lose) = if self.current_turn > 0 { (1, 0) } else { (0, 1) };
+        let (win, lose) = (layout.results[win].pos, layout.results[lose].pos);
+
+        self.win_image.pos = Vec3::new(win.x, win.y, GUI_RESULT_ORDER);
+        self.lose_image.pos = Vec3::new(lose.x, lose.y, GUI_RESULT_ORDER);
+        self.win_image.changed = true;
+        self.lose_image.changed = true;
+    }
+
+    pub fn set_status(&mut self, status: Status, locale: &Locale, renderer: &mut GpuRenderer) {
+        self.status = status;
+        self.set_locale(locale, renderer);
     }
 
-    pub fn change_status_text(&mut self, message: &str, renderer: &mut GpuRenderer) {
+    /// Formats the status text again in the Locale's language.
+    pub fn set_locale(&mut self, locale: &Locale, renderer: &mut GpuRenderer) {
+        let attrs = font_attrs(&self.font_family);
         self.status_text.set_text(
             renderer,
-            message,
-            Attrs::new(),
+            &self.status.message(locale),
+            attrs,
         );
     }
 
+    /// Ships still afloat on the board of player index.
+    pub fn ships_left(&self, index: usize) -> i32 {
+        self.ship_counter_data[index].iter().sum()
+    }
+
     pub fn update_ship_counter(&mut self, ship_count: &[i32; 4], renderer: &mut GpuRenderer, board_index: usize) {
         for y in 0..=3 {
-            self.ship_counter[board_index][y].set_text(renderer, &format!("{}", ship_count[y]), Attrs::new());
+            self.ship_counter[board_index][y].set_text(renderer, &format!("{}", ship_count[y]), font_attrs(&self.font_family));
         }
     }
 
     pub fn reduce_ship_counter(&mut self, ship_size: usize, renderer: &mut GpuRenderer, board_index: usize) {
         self.ship_counter_data[board_index][ship_size] -= 1;
-        self.ship_counter[board_index][ship_size].set_text(renderer, &format!("{}", self.ship_counter_data[board_index][ship_size]), Attrs::new());
+        self.ship_counter[board_index][ship_size].set_text(renderer, &format!("{}", self.ship_counter_data[board_index][ship_size]), font_attrs(&self.font_family));
     }
 }
 
+/// Tiles of one player. The ships and markers on it are entities in the
+/// World with a BoardCell pointing back at index.
 pub struct Board {
+    pub index: usize,
     pub data: [BoardType; 256],
-    pub ship: Vec<Ship>,
-    pub icon: Vec<Image>,
     pub map: Map,
+    /// Colors the icons are tinted with.
+    pub markers: MarkerColors,
+    /// Set on boards a Spectator mirrors. hit_place leaves those alone so
+    /// shots only come in through the MatchHistory.
+    pub read_only: bool,
 }
 
 impl Board {
-    pub fn new(renderer: &mut GpuRenderer, pos: Vec2) -> Self {
+    pub fn new(renderer: &mut GpuRenderer, index: usize, pos: Vec2) -> Self {
         let mut data = Self {
+            index,
             data: [BoardType::None; 256],
-            ship: Vec::with_capacity(1),
-            icon: Vec::with_capacity(1),
-            map: Map::new(renderer, 20),
+            map: Map::new(renderer, TILE_SIZE),
+            markers: ColorMode::Normal.markers(),
+            read_only: false,
         };
         (0..BOARD_SIZE as u32).for_each(|x| {
             (0..BOARD_SIZE as u32).for_each(|y| {
@@ -238,6 +262,23 @@ impl Board {
         data
     }
 
+    /// Tints the icons with the colour modes marker colours.
+    pub fn set_accessibility(&mut self, world: &mut World, accessibility: &Accessibility) {
+        self.markers = accessibility.markers();
+        tint_markers(world, self.index, &self.markers);
+    }
+
+    /// Moves the map along with every placed ship and icon.
+    pub fn set_pos(&mut self, world: &mut World, pos: Vec2) {
+        let offset = pos - self.map.pos;
+        if offset == Vec2::ZERO { return; }
+
+        move_board_entities(world, self.index, offset);
+
+        self.map.pos = pos;
+        self.map.changed = true;
+    }
+
     pub fn count_ship(&mut self) -> i32 {
         let mut ship_index: Vec<i32>;
         ship_index = Vec::with_capacity(1);
@@ -347,63 +388,60 @@ impl Board {
         result
     }
     
-    pub fn place_ship(&mut self, size: usize, index: i32, resource: &TextureAllocation, renderer: &mut GpuRenderer) -> bool {
+    pub fn place_ship(&mut self, world: &mut World, size: usize, index: i32, resource: &TextureAllocation, renderer: &mut GpuRenderer) -> bool {
         let result = self.try_place_ship(index, size);
         if result.is_none() { return false; }
     
         let mut rng = rand::thread_rng();
         let result_data = result.unwrap();
         let sprite_pos = tile_to_render_pos(&result_data.1, self.map.pos, 20.0);
-        let mut ship = Ship::new(resource, renderer, index, (size + 1) as i32);
-    
-        if size >= 1 {
-            let random_number = rng.gen_range(0..=1);
-            if result_data.0 == 0 { // Vertical
-                ship.sprite.pos = Vec3::new(sprite_pos.x, sprite_pos.y, SHIP_ORDER);
-                ship.sprite.hw = Vec2::new(20.0, 20.0 + (20.0 * size as f32));
-                match size {
-                    1 => { ship.sprite.uv = Vec4::new(40.0 + (20.0 * (random_number as f32)), 20.0, 20.0, 40.0); },
-                    2 => { ship.sprite.uv = Vec4::new(60.0 + (20.0 * (random_number as f32)), 60.0, 20.0, 60.0); },
-                    _ => { ship.sprite.uv = Vec4::new(100.0, 0.0 + (80.0 * (random_number as f32)), 20.0, 80.0); },
-                }
-            } else { // Horizontal
-                ship.sprite.pos = Vec3::new(sprite_pos.x, sprite_pos.y, SHIP_ORDER);
-                ship.sprite.hw = Vec2::new(20.0 + (20.0 * size as f32), 20.0);
-                match size {
-                    1 => { ship.sprite.uv = Vec4::new(0.0, 20.0 + (20.0 * (random_number as f32)), 40.0, 20.0); },
-                    2 => { ship.sprite.uv = Vec4::new(0.0, 60.0 + (20.0 * (random_number as f32)), 60.0, 20.0); },
-                    _ => { ship.sprite.uv = Vec4::new(0.0, 120.0 + (20.0 * (random_number as f32)), 80.0, 20.0); },
-                }
-            }
-        } else {
-            let random_number = rng.gen_range(0..=3);
-            ship.sprite.pos = Vec3::new(sprite_pos.x, sprite_pos.y, SHIP_ORDER);
-            ship.sprite.hw = Vec2::new(20.0, 20.0);
-            ship.sprite.uv = Vec4::new(20.0 * random_number as f32, 0.0, 20.0, 20.0);
-        }
-        ship.sprite.color = Color::rgba(255, 255, 255, 255);
-        self.ship.push(ship);
+        let variant = if size >= 1 { rng.gen_range(0..=1) } else { rng.gen_range(0..=3) };
+        let (hw, uv) = ship_sprite(size, result_data.0 == 0, variant);
+
+        // Ships stay hidden until they are sunk.
+        let mut sprite = Sprite::new(SpriteTexture::Ship, resource, renderer, uv);
+        sprite.visible = false;
+
+        world.spawn((
+            Transform::new(Vec3::new(sprite_pos.x, sprite_pos.y, SHIP_ORDER), hw),
+            sprite,
+            BoardCell { board: self.index, tile: result_data.1 },
+            Ship { index, parts: (size + 1) as i32 },
+        ));
     
         true
     }
 
-    pub fn prepare_board(&mut self, resource: &TextureAllocation, renderer: &mut GpuRenderer, gameboard: &mut GameBoard, board_index: usize) {
+    pub fn prepare_board(&mut self, world: &mut World, resource: &TextureAllocation, renderer: &mut GpuRenderer, gameboard: &mut GameBoard) {
         let mut cur_index = 0;
         for x in 0..=3 {
             for _i in 0..=gameboard.size_count[x] - 1 {
-                if self.place_ship(x, cur_index, resource, renderer) { cur_index += 1 }
+                if self.place_ship(world, x, cur_index, resource, renderer) { cur_index += 1 }
             }
         }
         let size_count = gameboard.size_count.clone();
-        gameboard.update_ship_counter(&size_count, renderer, board_index);
+        gameboard.update_ship_counter(&size_count, renderer, self.index);
     }
 
-    pub fn find_ship(&mut self, index: i32) -> Option<usize> {
-        if let Some(location) = self.ship.iter().position(|ship| ship.index == index) {
-            Some(location)
-        } else {
-            None
-        }
+    pub fn find_ship(&self, world: &World, index: i32) -> Option<Entity> {
+        world.query::<(&Ship, &BoardCell)>()
+            .iter()
+            .find(|(_, (ship, cell))| cell.board == self.index && ship.index == index)
+            .map(|(entity, _)| entity)
+    }
+
+    /// Places a hit or miss marker on the tile.
+    pub fn place_marker(&self, world: &mut World, pos: &Vec2, hit: bool, resource: &TextureAllocation, renderer: &mut GpuRenderer) -> Entity {
+        let sprite_pos = tile_to_render_pos(pos, self.map.pos, 20.0);
+        let mut sprite = Sprite::new(SpriteTexture::Icon, resource, renderer, Vec4::new(if hit { 0.0 } else { 20.0 }, 0.0, 20.0, 20.0));
+        sprite.image.color = self.markers.tint(hit);
+
+        world.spawn((
+            Transform::new(Vec3::new(sprite_pos.x, sprite_pos.y, ICON_ORDER), Vec2::new(20.0, 20.0)),
+            sprite,
+            BoardCell { board: self.index, tile: *pos },
+            Marker { hit },
+        ))
     }
 
     pub fn count_size(&mut self, ship_index: i32) -> i32 {
@@ -418,15 +456,34 @@ impl Board {
         count
     }
 
-    pub fn hit_place(&mut self, pos: &Vec2, resource: &TextureAllocation, renderer: &mut GpuRenderer, animation: &mut Animation, gameboard: &mut GameBoard) -> Option<bool> {
+    pub fn hit_place<B: AudioBackend>(&mut self, world: &mut World, pos: &Vec2, resource: &TextureAllocation, renderer: &mut GpuRenderer, effects: &mut Effects, seconds: f32, gameboard: &mut GameBoard, audio: &mut Audio<B>) -> Option<bool> {
+        if self.read_only { return None; }
+
         let mut result = None;
         if pos.x >= 0.0 && pos.x < BOARD_SIZE && pos.y >= 0.0 && pos.y < BOARD_SIZE {
             let tile_index = get_tile_pos(pos.x as i32, pos.y as i32);
+            // Center of the tile so sounds pan where it landed.
+            let tile_center = tile_to_render_pos(pos, self.map.pos, 20.0) + Vec2::new(10.0, 10.0);
+            if matches!(self.data[tile_index], BoardType::Ship(_) | BoardType::None) {
+                audio.emit(AudioEvent::at(AudioEventKind::ShotFired, tile_center));
+            }
+
             if let BoardType::Ship(index) = self.data[tile_index] {
-                let ship_index = self.find_ship(index).unwrap();
+                let ship = self.find_ship(world, index).unwrap();
                 self.data[tile_index] = BoardType::Hit(index);
 
-                if self.ship[ship_index].damage_ship() {
+                audio.emit(AudioEvent::at(AudioEventKind::Hit, tile_center));
+                effects.play(EffectKind::Explosion, BoardCell { board: self.index, tile: *pos }, seconds);
+
+                let sunk = {
+                    let (ship, sprite) = world.query_one_mut::<(&mut Ship, &mut Sprite)>(ship).unwrap();
+                    let sunk = ship.damage_ship();
+                    if sunk { sprite.visible = true; }
+                    sunk
+                };
+                if sunk {
+                    effects.sink(world, ship, seconds);
+                    audio.emit(AudioEvent::at(AudioEventKind::ShipSunk, tile_center));
                     if self.count_ship() <= 0 {
                         result = Some(true);
                     } else {
@@ -439,35 +496,74 @@ impl Board {
                     result = Some(false);
                 }
 
-                let sprite_pos = tile_to_render_pos(pos, self.map.pos, 20.0);
-                let mut icon = Image::new(Some(resource.icon_texture), renderer, 1);
-                icon.pos = Vec3::new(sprite_pos.x, sprite_pos.y, ICON_ORDER);
-                icon.hw = Vec2::new(20.0, 20.0);
-                icon.uv = Vec4::new(0.0, 0.0, 20.0, 20.0);
-                icon.color = Color::rgba(255, 255, 255, 255);
-                self.icon.push(icon);
-                animation.play(Vec2::new(sprite_pos.x - 20.0, sprite_pos.y - 20.0));
+                self.place_marker(world, pos, true, resource, renderer);
             } else if self.data[tile_index] == BoardType::None {
                 self.data[tile_index] = BoardType::Missed;
-                let sprite_pos = tile_to_render_pos(pos, self.map.pos, 20.0);
-                let mut icon = Image::new(Some(resource.icon_texture), renderer, 1);
-                icon.pos = Vec3::new(sprite_pos.x, sprite_pos.y, ICON_ORDER);
-                icon.hw = Vec2::new(20.0, 20.0);
-                icon.uv = Vec4::new(20.0, 0.0, 20.0, 20.0);
-                icon.color = Color::rgba(255, 255, 255, 255);
-                self.icon.push(icon);
+                audio.emit(AudioEvent::at(AudioEventKind::Splash, tile_center));
+                effects.play(EffectKind::Splash, BoardCell { board: self.index, tile: *pos }, seconds);
+                self.place_marker(world, pos, false, resource, renderer);
 
                 result = Some(false);
             }
         }
         result
     }
+
+    /// Shows a shot taken elsewhere, drawing the ship it sank if any.
+    pub fn show_shot(&mut self, world: &mut World, shot: &Shot, resource: &TextureAllocation, renderer: &mut GpuRenderer, effects: &mut Effects, seconds: f32) {
+        let pos = Vec2::new(shot.tile.0 as f32, shot.tile.1 as f32);
+        shot.apply(&mut self.data);
+
+        let hit = shot.outcome != ShotOutcome::Miss;
+        let kind = if hit { EffectKind::Explosion } else { EffectKind::Splash };
+        self.place_marker(world, &pos, hit, resource, renderer);
+        effects.play(kind, BoardCell { board: self.index, tile: pos }, seconds);
+
+        if let Some(extent) = shot.outcome.sunk() {
+            let tile = Vec2::new(extent.tile.0 as f32, extent.tile.1 as f32);
+            let ship_pos = tile_to_render_pos(&tile, self.map.pos, 20.0);
+            let (hw, uv) = ship_sprite(extent.size as usize - 1, extent.vertical, 0);
+
+            let ship = world.spawn((
+                Transform::new(Vec3::new(ship_pos.x, ship_pos.y, SHIP_ORDER), hw),
+                Sprite::new(SpriteTexture::Ship, resource, renderer, uv),
+                BoardCell { board: self.index, tile },
+                Ship { index: UNKNOWN_SHIP, parts: 0 },
+            ));
+            effects.sink(world, ship, seconds);
+        }
+    }
+}
+
+/// Size and texture rect of the ship sprite. size counts the tiles after
+/// the first and variant picks one of the looks drawn for that size.
+pub fn ship_sprite(size: usize, vertical: bool, variant: u32) -> (Vec2, Vec4) {
+    let variant = variant as f32;
+    let length = 20.0 + (20.0 * size as f32);
+
+    if size == 0 {
+        (Vec2::new(20.0, 20.0), Vec4::new(20.0 * variant, 0.0, 20.0, 20.0))
+    } else if vertical {
+        let uv = match size {
+            1 => Vec4::new(40.0 + (20.0 * variant), 20.0, 20.0, 40.0),
+            2 => Vec4::new(60.0 + (20.0 * variant), 60.0, 20.0, 60.0),
+            _ => Vec4::new(100.0, 0.0 + (80.0 * variant), 20.0, 80.0),
+        };
+        (Vec2::new(20.0, length), uv)
+    } else {
+        let uv = match size {
+            1 => Vec4::new(0.0, 20.0 + (20.0 * variant), 40.0, 20.0),
+            2 => Vec4::new(0.0, 60.0 + (20.0 * variant), 60.0, 20.0),
+            _ => Vec4::new(0.0, 120.0 + (20.0 * variant), 80.0, 20.0),
+        };
+        (Vec2::new(length, 20.0), uv)
+    }
 }
 
-pub fn in_map_pos(pos: &Vec2, boards: &[Board], screen_size: &PhysicalSize<f32>) -> Option<Vec2> {
+pub fn in_map_pos(pos: &Vec2, boards: &[Board], screen_size: &PhysicalSize<f32>, zoom: f32) -> Option<Vec2> {
     if boards.is_empty() { return None; }
 
-    let mouse_pos = world_to_sprite_2pos(pos, screen_size);
+    let mouse_pos = world_to_sprite_2pos(pos, screen_size, zoom);
 
     let board_result = boards.iter().find(|board| {
         (mouse_pos.x) >= board.map.pos.x
@@ -481,8 +577,8 @@ pub fn in_map_pos(pos: &Vec2, boards: &[Board], screen_size: &PhysicalSize<f32>)
     Some(Vec2::new((tile_pos.x / board_data.map.tilesize as f32).floor(), (tile_pos.y / board_data.map.tilesize as f32).floor()))
 }
 
-pub fn find_map_by_pos(pos: &Vec2, boards: &[Board], screen_size: &PhysicalSize<f32>) -> Option<u32> {
-    let mouse_pos = world_to_sprite_2pos(pos, screen_size);
+pub fn find_map_by_pos(pos: &Vec2, boards: &[Board], screen_size: &PhysicalSize<f32>, zoom: f32) -> Option<u32> {
+    let mouse_pos = world_to_sprite_2pos(pos, screen_size, zoom);
     let mut result: u32 = 0;
 
     for i in 0..=boards.len() {
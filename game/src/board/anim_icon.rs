@@ -1,52 +1,232 @@
 use graphics::{ *};
+use hecs::{Entity, World};
+use input::Scheduler;
+use std::collections::VecDeque;
+use super::Board;
+use crate::ecs::{AnimationState, BoardCell, Effect, EffectKind, Fade, Sprite, SpriteTexture, Transform};
+use crate::func::tile_to_render_pos;
 use crate::EXPLOSION_ORDER;
 use crate::TextureAllocation;
 
-pub struct Animation {
-    pub sprite: Image,
-    pub in_play: bool,
-    pub frame: i32,
-    pub max_frame: i32,
-    pub elapsed_time: f32,
+/// Most effects playing at once. The rest wait in the queue for a slot.
+pub const MAX_EFFECTS: usize = 32;
+/// Frames in the effect sheet.
+const EFFECT_FRAMES: i32 = 9;
+/// Alpha a sunk ship is left at.
+const SUNK_ALPHA: u8 = 110;
+const SINK_FADE_TIME: f32 = 0.6;
+/// Delay between the tiles of a ship catching fire.
+const SINK_TILE_DELAY: f32 = 0.08;
+
+impl EffectKind {
+    /// Seconds each frame is shown for.
+    pub fn frame_time(&self) -> f32 {
+        match self {
+            EffectKind::Explosion => 0.07,
+            EffectKind::Splash => 0.06,
+            EffectKind::Fire => 0.09,
+            EffectKind::Smoke => 0.12,
+        }
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.frame_time() * EFFECT_FRAMES as f32
+    }
+
+    /// Every effect uses the explosion sheet, tinted to tell them apart.
+    /// There is no splash sheet yet, so a splash is a blue explosion.
+    pub fn tint(&self) -> Color {
+        match self {
+            EffectKind::Explosion => Color::rgba(255, 255, 255, 255),
+            EffectKind::Splash => Color::rgba(110, 180, 255, 255),
+            EffectKind::Fire => Color::rgba(255, 150, 60, 255),
+            EffectKind::Smoke => Color::rgba(150, 150, 150, 220),
+        }
+    }
+
+    pub fn size(&self) -> Vec2 {
+        match self {
+            EffectKind::Explosion => Vec2::new(60.0, 60.0),
+            _ => Vec2::new(40.0, 40.0),
+        }
+    }
+}
+
+/// Effect waiting for its start time or a free slot. It keeps the tile
+/// rather than a position so it still lands on it if the board moves.
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct QueuedEffect {
+    kind: EffectKind,
+    cell: BoardCell,
+}
+
+/// Queues effects and spawns them as entities once they are due, so any
+/// number can play at once and none are dropped.
+#[derive(Default)]
+pub struct Effects {
+    scheduled: Scheduler<QueuedEffect>,
+    /// Effects that are due, waiting for a free slot.
+    queue: VecDeque<QueuedEffect>,
+    /// Skips playing so nothing flashes on screen.
+    pub reduced_motion: bool,
 }
 
-impl Animation {
-    pub fn new(resource: &TextureAllocation, renderer: &mut GpuRenderer) -> Self {
-        let mut result = Self {
-            sprite: Image::new(Some(resource.explosion_texture), renderer, 1),
-            in_play: false,
-            frame: -1,
-            max_frame: 9,
-            elapsed_time: 0.0,
+impl Effects {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Plays kind centered on the tile once start seconds is reached.
+    pub fn play(&mut self, kind: EffectKind, cell: BoardCell, start: f32) {
+        if self.reduced_motion { return; }
+
+        self.scheduled.at(start, QueuedEffect { kind, cell });
+    }
+
+    /// Sets every tile of the ship on fire, then smoke, then fades the
+    /// wreck down.
+    pub fn sink(&mut self, world: &mut World, ship: Entity, seconds: f32) {
+        let (transform, cell) = match world.query_one_mut::<(&Transform, &BoardCell)>(ship) {
+            Ok((transform, cell)) => (*transform, *cell),
+            Err(_) => return,
+        };
+
+        let vertical = transform.size.y > transform.size.x;
+        let tiles = (transform.size.x.max(transform.size.y) / 20.0).round() as usize;
+        let mut fade_start = seconds;
+
+        for tile in 0..tiles {
+            let offset = if vertical { Vec2::new(0.0, tile as f32) } else { Vec2::new(tile as f32, 0.0) };
+            let cell = BoardCell { board: cell.board, tile: cell.tile + offset };
+            let start = seconds + SINK_TILE_DELAY * tile as f32;
+
+            self.play(EffectKind::Fire, cell, start);
+            self.play(EffectKind::Smoke, cell, start + EffectKind::Fire.duration());
+            fade_start = start + EffectKind::Fire.duration();
+        }
+
+        let fade = if self.reduced_motion {
+            Fade { from: 255, to: SUNK_ALPHA, start: seconds, duration: 0.0 }
+        } else {
+            Fade { from: 255, to: SUNK_ALPHA, start: fade_start, duration: SINK_FADE_TIME }
         };
-        result.sprite.pos = Vec3::new(0.0, 0.0, EXPLOSION_ORDER);
-        result.sprite.hw = Vec2::new(60.0, 60.0);
-        result.sprite.uv = Vec4::new(60.0 * 5.0, 0.0, 60.0, 60.0);
-        result.sprite.color = Color::rgba(255, 255, 255, 255);
-        result
-    }
-
-    pub fn update_frame(&mut self, seconds: f32) {
-        if self.in_play {
-            if self.elapsed_time + 0.07 <= seconds {
-                self.elapsed_time = seconds;
-                self.frame += 1;
-                if self.frame >= self.max_frame {
-                    self.in_play = false;
-                    self.frame = -1;
-                } else {
-                    self.sprite.uv = Vec4::new(60.0 * (self.frame as f32), 0.0, 60.0, 60.0);
-                    self.sprite.changed = true;
-                }
+        let _ = world.insert_one(ship, fade);
+    }
+
+    /// Spawns every effect that is due while there are free slots, on its
+    /// tile where the boards are now.
+    pub fn update(&mut self, world: &mut World, boards: &[Board], resource: &TextureAllocation, renderer: &mut GpuRenderer, seconds: f32) {
+        let playing = world.query::<&Effect>().iter().count();
+
+        for effect in self.due(playing, seconds) {
+            if let Some(board) = boards.get(effect.cell.board) {
+                spawn_effect(world, board, resource, renderer, &effect, seconds);
             }
         }
     }
 
-    pub fn play(&mut self, pos: Vec2) {
-        if !self.in_play {
-            self.sprite.pos = Vec3::new(pos.x, pos.y, EXPLOSION_ORDER);
-            self.sprite.changed = true;
-            self.in_play = true;
+    /// Takes the effects that are due, at most as many as there are free
+    /// slots with playing effects on screen. Ones that are due keep their
+    /// order.
+    fn due(&mut self, playing: usize, seconds: f32) -> Vec<QueuedEffect> {
+        self.queue.extend(self.scheduled.update(seconds).into_iter().map(|(_, effect)| effect));
+
+        let free = MAX_EFFECTS.saturating_sub(playing);
+        self.queue.drain(..free.min(self.queue.len())).collect()
+    }
+}
+
+/// The effect gets the BoardCell so it moves along with the board.
+fn spawn_effect(world: &mut World, board: &Board, resource: &TextureAllocation, renderer: &mut GpuRenderer, effect: &QueuedEffect, seconds: f32) {
+    let kind = effect.kind;
+    let size = kind.size();
+    let first_uv = Vec4::new(0.0, 0.0, 60.0, 60.0);
+
+    let mut sprite = Sprite::new(SpriteTexture::Explosion, resource, renderer, first_uv);
+    sprite.image.color = kind.tint();
+
+    let mut animation = AnimationState::new(first_uv, EFFECT_FRAMES, kind.frame_time());
+    animation.play(seconds);
+
+    let center = tile_to_render_pos(&effect.cell.tile, board.map.pos, 20.0) + Vec2::new(10.0, 10.0);
+    let pos = center - size * 0.5;
+    let transform = Transform::new(Vec3::new(pos.x, pos.y, EXPLOSION_ORDER), size);
+
+    let entity = world.spawn((transform, sprite, animation, effect.cell, Effect { kind }));
+
+    // Smoke thins out over its whole animation.
+    if kind == EffectKind::Smoke {
+        let fade = Fade { from: kind.tint().a(), to: 0, start: seconds, duration: kind.duration() };
+        let _ = world.insert_one(entity, fade);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(x: f32, y: f32) -> BoardCell {
+        BoardCell { board: 1, tile: Vec2::new(x, y) }
+    }
+
+    #[test]
+    fn due_effects_keep_their_order() {
+        let mut effects = Effects::new();
+        effects.play(EffectKind::Splash, cell(0.0, 0.0), 2.0);
+        effects.play(EffectKind::Explosion, cell(1.0, 0.0), 1.0);
+        effects.play(EffectKind::Splash, cell(2.0, 0.0), 0.5);
+
+        let columns = |due: Vec<QueuedEffect>| due.iter().map(|effect| effect.cell.tile.x).collect::<Vec<_>>();
+        assert!(effects.due(0, 0.0).is_empty());
+        assert_eq!(columns(effects.due(0, 1.0)), [2.0, 1.0]);
+        assert_eq!(columns(effects.due(0, 5.0)), [0.0]);
+        assert!(effects.scheduled.is_empty() && effects.queue.is_empty());
+    }
+
+    #[test]
+    fn only_max_effects_play_at_once() {
+        let mut effects = Effects::new();
+        for x in 0..MAX_EFFECTS + 8 {
+            effects.play(EffectKind::Explosion, cell(x as f32, 0.0), 0.0);
         }
+
+        assert_eq!(effects.due(0, 0.0).len(), MAX_EFFECTS);
+        // Nothing more starts while every slot is still playing.
+        assert!(effects.due(MAX_EFFECTS, 0.0).is_empty());
+        assert_eq!(effects.due(MAX_EFFECTS - 3, 0.0).len(), 3);
+        assert_eq!(effects.queue.len(), 5);
+    }
+
+    #[test]
+    fn reduced_motion_queues_nothing() {
+        let mut effects = Effects::new();
+        effects.reduced_motion = true;
+        effects.play(EffectKind::Explosion, cell(0.0, 0.0), 0.0);
+
+        assert!(effects.scheduled.is_empty());
+    }
+
+    #[test]
+    fn sinking_burns_then_smokes_then_fades() {
+        let mut world = World::new();
+        let ship = world.spawn((Transform::new(Vec3::new(0.0, 0.0, 1.0), Vec2::new(20.0, 60.0)), cell(2.0, 3.0)));
+
+        let mut effects = Effects::new();
+        effects.sink(&mut world, ship, 1.0);
+
+        let kinds = |due: Vec<QueuedEffect>| due.iter().map(|effect| (effect.kind, effect.cell)).collect::<Vec<_>>();
+        let burn = EffectKind::Fire.duration();
+        let last = 1.0 + SINK_TILE_DELAY * 2.0;
+
+        assert_eq!(kinds(effects.due(0, 1.0)), [(EffectKind::Fire, cell(2.0, 3.0))]);
+        assert_eq!(kinds(effects.due(0, last)), [(EffectKind::Fire, cell(2.0, 4.0)), (EffectKind::Fire, cell(2.0, 5.0))]);
+        assert_eq!(kinds(effects.due(0, 1.0 + burn)), [(EffectKind::Smoke, cell(2.0, 3.0))]);
+
+        // The wreck fades once the last tile stops burning.
+        let fade = *world.get::<&Fade>(ship).unwrap();
+        assert_eq!(fade.start, last + burn);
+        assert_eq!(fade.to, SUNK_ALPHA);
+        assert_eq!(kinds(effects.due(0, fade.start)), [(EffectKind::Smoke, cell(2.0, 4.0)), (EffectKind::Smoke, cell(2.0, 5.0))]);
+        assert!(effects.scheduled.is_empty());
     }
 }
@@ -0,0 +1,109 @@
+use super::{Board, BoardType};
+use crate::accessibility::MarkerColors;
+use crate::func::*;
+use crate::theme::Palette;
+use crate::{BOARD_SIZE, OUTLINE_ORDER};
+use graphics::*;
+
+/// High contrast shapes drawn over the boards so hits, misses and the board
+/// being fired at do not rely on the icon art or the shade alone.
+pub struct BoardOutline {
+    pub mesh: Mesh2D,
+    /// Set when anything it outlines moved or changed so it gets rebuilt.
+    pub changed: bool,
+}
+
+impl BoardOutline {
+    pub fn new(renderer: &mut GpuRenderer) -> Self {
+        Self {
+            mesh: Mesh2D::new(renderer),
+            changed: true,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mesh.vertices.is_empty()
+    }
+
+    /// Builds the shapes again from the boards. target is the board being
+    /// fired at if the game is still going.
+    pub fn rebuild(
+        &mut self,
+        boards: &[Board],
+        target: Option<usize>,
+        markers: &MarkerColors,
+    ) -> Result<(), AscendingError> {
+        let mut builder = Mesh2DBuilder::with_camera();
+        let board_size = BOARD_SIZE * 20.0;
+
+        for (index, board) in boards.iter().enumerate() {
+            for (tile, data) in board.data.iter().enumerate() {
+                let tile_pos = Vec2::new(
+                    find_x_base_on_tile(tile) as f32,
+                    find_y_base_on_tile(tile) as f32,
+                );
+                if tile_pos.y >= BOARD_SIZE {
+                    break;
+                }
+
+                let pos = tile_to_render_pos(&tile_pos, board.map.pos, 20.0);
+                match data {
+                    BoardType::Hit(_) => {
+                        let color = Palette::color(markers.hit);
+                        builder.line(
+                            &[
+                                pos + Vec2::new(4.0, 4.0),
+                                pos + Vec2::new(16.0, 16.0),
+                            ],
+                            OUTLINE_ORDER,
+                            2.0,
+                            color,
+                        )?;
+                        builder.line(
+                            &[
+                                pos + Vec2::new(4.0, 16.0),
+                                pos + Vec2::new(16.0, 4.0),
+                            ],
+                            OUTLINE_ORDER,
+                            2.0,
+                            color,
+                        )?;
+                    }
+                    BoardType::Missed => {
+                        builder.circle(
+                            DrawMode::stroke(2.0),
+                            pos + Vec2::new(10.0, 10.0),
+                            5.0,
+                            0.1,
+                            OUTLINE_ORDER,
+                            Palette::color(markers.miss),
+                        )?;
+                    }
+                    _ => {}
+                }
+            }
+
+            if target == Some(index) {
+                let bounds = Vec4::new(
+                    board.map.pos.x + 1.0,
+                    board.map.pos.y + 1.0,
+                    board_size - 2.0,
+                    board_size - 2.0,
+                );
+                builder.rectangle(
+                    DrawMode::stroke(2.0),
+                    bounds,
+                    OUTLINE_ORDER,
+                    Palette::color(markers.target),
+                )?;
+            }
+        }
+
+        self.mesh.vertices.clear();
+        self.mesh.indices.clear();
+        self.mesh.from_builder(builder.finalize());
+        self.mesh.changed = true;
+        self.changed = false;
+        Ok(())
+    }
+}
@@ -1,16 +1,29 @@
 use graphics::{ *};
 
-pub const ACTION_SIZE: usize = 2;
+/// Zoom the window is first opened at before it is fit to the screen.
 pub const SCREEN_ZOOM: f32 = 2.0;
+/// Font size in sprite units. Text gets scaled up by the zoom.
+pub const FONT_SIZE: f32 = 8.0;
 
 pub const BOARD_SIZE: f32 = 12.0;
+pub const TILE_SIZE: u32 = 20;
 
 pub const SHIP_ORDER: f32 = 3.2;
 pub const ICON_ORDER: f32 = 3.1;
 pub const EXPLOSION_ORDER: f32 = 3.0;
 pub const GUI_BG_ORDER: f32 = 2.2;
 pub const GUI_SHADE_ORDER: f32 = 2.1;
+pub const OUTLINE_ORDER: f32 = 2.05;
 pub const GUI_RESULT_ORDER: f32 = 2.0;
+pub const GUI_OPTIONS_ORDER: f32 = 1.9;
+pub const GUI_CHAT_ORDER: f32 = 1.8;
+pub const GUI_CHAT_SELECTION_ORDER: f32 = 1.75;
+pub const GUI_CHAT_CARET_ORDER: f32 = 1.7;
+
+/// Input context pushed while the options menu is open.
+pub const MENU_CONTEXT: &str = "menu";
+/// Input context pushed while typing in the chat.
+pub const CHAT_CONTEXT: &str = "chat";
 
 pub struct TextureAllocation {
     pub ship_texture: Allocation,
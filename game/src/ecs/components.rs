@@ -0,0 +1,183 @@
+use graphics::*;
+use input::Timer;
+
+use crate::TextureAllocation;
+
+/// Where an entity is drawn in sprite units. z is its render order.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transform {
+    pub pos: Vec3,
+    pub size: Vec2,
+}
+
+impl Transform {
+    pub fn new(pos: Vec3, size: Vec2) -> Self {
+        Self { pos, size }
+    }
+}
+
+/// Which of the Themes textures a Sprite uses so it can be swapped over
+/// when the Theme changes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SpriteTexture {
+    Ship,
+    Icon,
+    Explosion,
+    GameBg,
+}
+
+impl SpriteTexture {
+    pub fn allocation(&self, resource: &TextureAllocation) -> Allocation {
+        match self {
+            SpriteTexture::Ship => resource.ship_texture,
+            SpriteTexture::Icon => resource.icon_texture,
+            SpriteTexture::Explosion => resource.explosion_texture,
+            SpriteTexture::GameBg => resource.game_bg_texture,
+        }
+    }
+}
+
+/// Image drawn at the entities Transform.
+pub struct Sprite {
+    pub image: Image,
+    pub texture: SpriteTexture,
+    /// Hidden sprites are not sent to the renderer.
+    pub visible: bool,
+}
+
+impl Sprite {
+    pub fn new(
+        texture: SpriteTexture,
+        resource: &TextureAllocation,
+        renderer: &mut GpuRenderer,
+        uv: Vec4,
+    ) -> Self {
+        let mut image =
+            Image::new(Some(texture.allocation(resource)), renderer, 1);
+        image.uv = uv;
+        image.color = Color::rgba(255, 255, 255, 255);
+
+        Self {
+            image,
+            texture,
+            visible: true,
+        }
+    }
+}
+
+/// Tile of a Board the entity sits on. Ships use the tile they start from.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BoardCell {
+    pub board: usize,
+    pub tile: Vec2,
+}
+
+/// Ship hidden on a Board until every part of it is hit.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Ship {
+    pub index: i32,
+    pub parts: i32,
+}
+
+impl Ship {
+    /// Returns true once the last part is hit.
+    pub fn damage_ship(&mut self) -> bool {
+        self.parts -= 1;
+        self.parts <= 0
+    }
+}
+
+/// Hit or Miss marker placed on a tile that was fired at.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Marker {
+    pub hit: bool,
+}
+
+/// Steps a Sprite through the frames of a sheet laid out left to right,
+/// starting from first_uv. The Sprite is only visible while it plays.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AnimationState {
+    pub first_uv: Vec4,
+    pub frame: i32,
+    pub max_frame: i32,
+    /// Seconds each frame is shown for.
+    pub frame_time: f32,
+    /// Goes off when the next frame is due.
+    pub timer: Timer,
+    pub in_play: bool,
+}
+
+impl AnimationState {
+    pub fn new(first_uv: Vec4, max_frame: i32, frame_time: f32) -> Self {
+        Self {
+            first_uv,
+            frame: -1,
+            max_frame,
+            frame_time,
+            timer: Timer::repeating(0.0, frame_time),
+            in_play: false,
+        }
+    }
+
+    /// Starts from the first frame, shown at seconds.
+    pub fn play(&mut self, seconds: f32) {
+        self.frame = -1;
+        self.timer = Timer::repeating(seconds, self.frame_time);
+        self.in_play = true;
+    }
+
+    pub fn frame_uv(&self) -> Vec4 {
+        let uv = self.first_uv;
+        Vec4::new(uv.x + uv.z * self.frame as f32, uv.y, uv.z, uv.w)
+    }
+}
+
+/// One shot effect that is removed once its animation finishes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Effect {
+    pub kind: EffectKind,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EffectKind {
+    /// Hit on a ship.
+    Explosion,
+    /// Shot that missed. Drawn with the explosion sheet tinted blue until
+    /// a splash sheet is made.
+    Splash,
+    /// First stage of a ship sinking.
+    Fire,
+    /// Second stage of a ship sinking.
+    Smoke,
+}
+
+/// Moves the alpha of a Sprite from one value to another, starting at
+/// start seconds. Removed once it is done.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Fade {
+    pub from: u8,
+    pub to: u8,
+    pub start: f32,
+    pub duration: f32,
+}
+
+impl Fade {
+    pub fn alpha(&self, seconds: f32) -> u8 {
+        let amount = if self.duration > 0.0 {
+            ((seconds - self.start) / self.duration).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+
+        (self.from as f32 + (self.to as f32 - self.from as f32) * amount)
+            .round() as u8
+    }
+
+    pub fn is_done(&self, seconds: f32) -> bool {
+        seconds >= self.start + self.duration
+    }
+}
+
+/// Tags the background panel of the GUI.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct GuiPanel;
@@ -0,0 +1,156 @@
+use graphics::*;
+use hecs::World;
+
+use super::{
+    AnimationState, BoardCell, Effect, Fade, Marker, Sprite, Transform,
+};
+use crate::accessibility::MarkerColors;
+use crate::TextureAllocation;
+
+/// Steps every playing animation forward, hiding its Sprite once the last
+/// frame was shown.
+pub fn update_animations(world: &mut World, seconds: f32) {
+    for (_, (animation, sprite)) in
+        world.query_mut::<(&mut AnimationState, &mut Sprite)>()
+    {
+        if !animation.in_play {
+            continue;
+        }
+
+        if animation.timer.tick(seconds) {
+            animation.frame += 1;
+            if animation.frame >= animation.max_frame {
+                animation.in_play = false;
+                animation.frame = -1;
+            } else {
+                sprite.image.uv = animation.frame_uv();
+                sprite.image.changed = true;
+            }
+        }
+
+        sprite.visible = animation.in_play;
+    }
+}
+
+/// Applies every Fade that has started, removing the ones that are done.
+pub fn update_fades(world: &mut World, seconds: f32) {
+    let mut done = Vec::new();
+
+    for (entity, (fade, sprite)) in world.query_mut::<(&Fade, &mut Sprite)>() {
+        if seconds < fade.start {
+            continue;
+        }
+
+        let color = sprite.image.color;
+        sprite.image.color =
+            Color::rgba(color.r(), color.g(), color.b(), fade.alpha(seconds));
+        sprite.image.changed = true;
+
+        if fade.is_done(seconds) {
+            done.push(entity);
+        }
+    }
+
+    for entity in done {
+        let _ = world.remove_one::<Fade>(entity);
+    }
+}
+
+/// Despawns effects whose animation has finished.
+pub fn remove_finished_effects(world: &mut World) {
+    let finished: Vec<_> = world
+        .query_mut::<(&Effect, &AnimationState)>()
+        .into_iter()
+        .filter(|(_, (_, animation))| !animation.in_play)
+        .map(|(entity, _)| entity)
+        .collect();
+
+    for entity in finished {
+        let _ = world.despawn(entity);
+    }
+}
+
+/// Swaps every Sprite over to the Themes textures.
+pub fn set_sprite_theme(world: &mut World, resource: &TextureAllocation) {
+    for (_, sprite) in world.query_mut::<&mut Sprite>() {
+        sprite.image.texture = Some(sprite.texture.allocation(resource));
+        sprite.image.changed = true;
+    }
+}
+
+/// Moves everything sitting on the board along with it.
+pub fn move_board_entities(world: &mut World, board: usize, offset: Vec2) {
+    for (_, (transform, cell)) in
+        world.query_mut::<(&mut Transform, &BoardCell)>()
+    {
+        if cell.board == board {
+            transform.pos += Vec3::new(offset.x, offset.y, 0.0);
+        }
+    }
+}
+
+/// Tints the markers on the board with the colour modes marker colours.
+pub fn tint_markers(world: &mut World, board: usize, markers: &MarkerColors) {
+    for (_, (marker, cell, sprite)) in
+        world.query_mut::<(&Marker, &BoardCell, &mut Sprite)>()
+    {
+        if cell.board == board {
+            sprite.image.color = markers.tint(marker.hit);
+            sprite.image.changed = true;
+        }
+    }
+}
+
+/// Sends every visible Sprite to the renderer, moving it to its Transform
+/// first if that changed.
+pub fn submit_sprites(
+    world: &mut World,
+    sprite_renderer: &mut ImageRenderer,
+    renderer: &mut GpuRenderer,
+) {
+    for (_, (transform, sprite)) in
+        world.query_mut::<(&Transform, &mut Sprite)>()
+    {
+        if !sprite.visible {
+            continue;
+        }
+
+        let image = &mut sprite.image;
+        if image.pos != transform.pos || image.hw != transform.size {
+            image.pos = transform.pos;
+            image.hw = transform.size;
+            image.changed = true;
+        }
+
+        sprite_renderer.image_update(image, renderer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_entities_on_the_board_move() {
+        let mut world = World::new();
+        let transform =
+            Transform::new(Vec3::new(10.0, 20.0, 1.0), Vec2::new(20.0, 20.0));
+        let cell = |board| BoardCell {
+            board,
+            tile: Vec2::new(2.0, 3.0),
+        };
+
+        let moved = world.spawn((transform, cell(1)));
+        let other_board = world.spawn((transform, cell(0)));
+        let off_board = world.spawn((transform,));
+
+        move_board_entities(&mut world, 1, Vec2::new(5.0, -2.0));
+
+        let pos = |entity| world.get::<&Transform>(entity).unwrap().pos;
+        assert_eq!(pos(moved), Vec3::new(15.0, 18.0, 1.0));
+        assert_eq!(pos(other_board), transform.pos);
+        assert_eq!(pos(off_board), transform.pos);
+        // The tile stays the same, only where it is drawn moves.
+        assert_eq!(*world.get::<&BoardCell>(moved).unwrap(), cell(1));
+    }
+}
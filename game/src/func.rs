@@ -1,10 +1,11 @@
 use glam::f32::*;
+use graphics::{AscendingError, OtherError};
+use log::warn;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{fs, path::Path};
 use winit::dpi::PhysicalSize;
 
-use crate::{
-    BOARD_SIZE,
-    SCREEN_ZOOM,
-};
+use crate::BOARD_SIZE;
 
 pub fn get_tile_pos(x: i32, y: i32) -> usize {
     (x + (y * BOARD_SIZE as i32)) as usize
@@ -18,14 +19,59 @@ pub fn find_y_base_on_tile(tile: usize) -> usize {
     tile / BOARD_SIZE as usize
 }
 
-pub fn world_to_sprite_3pos(pos: &Vec3, size: &PhysicalSize<f32>) -> Vec3 {
-    Vec3::new(pos.x / SCREEN_ZOOM, (size.height - pos.y) / SCREEN_ZOOM, pos.z)
+pub fn world_to_sprite_3pos(pos: &Vec3, size: &PhysicalSize<f32>, zoom: f32) -> Vec3 {
+    Vec3::new(pos.x / zoom, (size.height - pos.y) / zoom, pos.z)
 }
 
-pub fn world_to_sprite_2pos(pos: &Vec2, size: &PhysicalSize<f32>) -> Vec2 {
-    Vec2::new(pos.x / SCREEN_ZOOM, (size.height - pos.y) / SCREEN_ZOOM)
+pub fn world_to_sprite_2pos(pos: &Vec2, size: &PhysicalSize<f32>, zoom: f32) -> Vec2 {
+    Vec2::new(pos.x / zoom, (size.height - pos.y) / zoom)
 }
 
 pub fn tile_to_render_pos(pos: &Vec2, map_start_pos: Vec2, tile_size: f32) -> Vec2 {
     Vec2::new(map_start_pos.x + (pos.x * tile_size), map_start_pos.y + (pos.y * tile_size))
-}
\ No newline at end of file
+}
+
+/// Steps index forward or back by step, wrapping around within len.
+pub fn cycle_index(index: usize, step: isize, len: usize) -> usize {
+    (index as isize + step).rem_euclid(len as isize) as usize
+}
+
+pub fn load_toml<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<T, AscendingError> {
+    let path = path.as_ref();
+    let data = fs::read_to_string(path)?;
+
+    toml::from_str(&data).map_err(|e| {
+        AscendingError::Other(OtherError::new(&format!("failed to load {}: {}", path.display(), e)))
+    })
+}
+
+pub fn save_toml<T: Serialize>(path: impl AsRef<Path>, data: &T) -> Result<(), AscendingError> {
+    let path = path.as_ref();
+    let data = toml::to_string_pretty(data).map_err(|e| {
+        AscendingError::Other(OtherError::new(&format!("failed to save {}: {}", path.display(), e)))
+    })?;
+
+    fs::write(path, data)?;
+    Ok(())
+}
+
+/// Loads every toml file within the folder sorted by file name. Files that
+/// fail to load are logged and skipped.
+pub fn load_toml_dir<T: DeserializeOwned>(dir: impl AsRef<Path>) -> Result<Vec<T>, AscendingError> {
+    let mut paths: Vec<_> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().map_or(false, |ext| ext == "toml"))
+        .collect();
+    paths.sort();
+
+    Ok(paths
+        .iter()
+        .filter_map(|path| match load_toml(path) {
+            Ok(data) => Some(data),
+            Err(e) => {
+                warn!("{}", e);
+                None
+            }
+        })
+        .collect())
+}
@@ -9,8 +9,6 @@ where
 {
     /// World Camera Controls and time. Deturmines how the world is looked at.
     pub system: System<Controls>,
-    /// Data stores for render types
-    pub guis: Vec<Image>,
     /// Atlas Groups for Textures in GPU
     pub image_atlas: AtlasGroup,
     pub text_atlas: TextAtlas,
@@ -19,6 +17,9 @@ where
     pub text_renderer: TextRenderer,
     pub sprite_renderer: ImageRenderer,
     pub map_renderer: MapRenderer,
+    pub mesh_renderer: Mesh2DRenderer,
+    /// Color the screen is cleared to. Set by the Theme.
+    pub clear_color: wgpu::Color,
 }
 
 impl<Controls> Pass for State<Controls>
@@ -36,12 +37,7 @@ where
                 view: renderer.frame_buffer().as_ref().expect("no frame view?"),
                 resolve_target: None,
                 ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(wgpu::Color {
-                        r: 0.0,
-                        g: 0.25,
-                        b: 0.5,
-                        a: 1.0,
-                    }),
+                    load: wgpu::LoadOp::Clear(self.clear_color),
                     store: wgpu::StoreOp::Store,
                 },
             })],
@@ -76,6 +72,8 @@ where
 
         pass.render_image(renderer, &self.sprite_renderer, &self.image_atlas);
 
+        pass.render_2dmeshs(renderer, &self.mesh_renderer);
+
         pass.render_upper_maps(renderer, &self.map_renderer, &self.map_atlas);
 
         pass.render_text(renderer, &self.text_renderer, &self.text_atlas);
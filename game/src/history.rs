@@ -0,0 +1,350 @@
+use graphics::*;
+use hecs::World;
+use serde::{Deserialize, Serialize};
+
+use crate::board::{Board, BoardType, Effects, GameBoard};
+use crate::func::{find_x_base_on_tile, find_y_base_on_tile, get_tile_pos};
+use crate::TextureAllocation;
+
+/// Ship index used on boards mirrored from a MatchHistory. Spectators only
+/// learn where shots landed, not which ship they hit.
+pub const UNKNOWN_SHIP: i32 = -1;
+
+/// Tiles a sunk ship covered, so it can be drawn without knowing the rest
+/// of the fleet.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShipExtent {
+    /// Top left tile of the ship.
+    pub tile: (u32, u32),
+    /// Number of tiles long.
+    pub size: u32,
+    pub vertical: bool,
+}
+
+impl ShipExtent {
+    /// Finds every tile of ship within data. None if it has no tiles.
+    pub fn find(data: &[BoardType], ship: i32) -> Option<Self> {
+        let tiles: Vec<(u32, u32)> = data
+            .iter()
+            .enumerate()
+            .filter(|(_, tile)| match tile {
+                BoardType::Ship(index) | BoardType::Hit(index) => {
+                    *index == ship
+                }
+                _ => false,
+            })
+            .map(|(i, _)| {
+                (find_x_base_on_tile(i) as u32, find_y_base_on_tile(i) as u32)
+            })
+            .collect();
+
+        let first = *tiles.first()?;
+        Some(Self {
+            tile: first,
+            size: tiles.len() as u32,
+            vertical: tiles.len() > 1 && tiles.iter().all(|t| t.0 == first.0),
+        })
+    }
+
+    pub fn tiles(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        (0..self.size).map(|i| {
+            if self.vertical {
+                (self.tile.0, self.tile.1 + i)
+            } else {
+                (self.tile.0 + i, self.tile.1)
+            }
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShotOutcome {
+    Miss,
+    Hit,
+    /// Hit that sank the ship.
+    Sunk(ShipExtent),
+    /// Hit that sank the last ship on the board.
+    Won(ShipExtent),
+}
+
+impl ShotOutcome {
+    /// The ship this shot sank if any.
+    pub fn sunk(&self) -> Option<ShipExtent> {
+        match *self {
+            ShotOutcome::Sunk(ship) | ShotOutcome::Won(ship) => Some(ship),
+            ShotOutcome::Miss | ShotOutcome::Hit => None,
+        }
+    }
+}
+
+/// One shot taken during the match.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Shot {
+    /// Board that was fired at.
+    pub board: usize,
+    /// Tile position within that board.
+    pub tile: (u32, u32),
+    pub outcome: ShotOutcome,
+}
+
+impl Shot {
+    /// Marks the shot on a board's tiles. Hit tiles use UNKNOWN_SHIP.
+    pub fn apply(&self, data: &mut [BoardType]) {
+        let index = get_tile_pos(self.tile.0 as i32, self.tile.1 as i32);
+        data[index] = match self.outcome {
+            ShotOutcome::Miss => BoardType::Missed,
+            _ => BoardType::Hit(UNKNOWN_SHIP),
+        };
+    }
+}
+
+/// Every shot of the match in the order they were taken. This is what a
+/// read only observer needs to catch up on a match already in progress and
+/// then follow along shot by shot.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct MatchHistory {
+    pub shots: Vec<Shot>,
+}
+
+impl MatchHistory {
+    /// Records the shot that was just fired at board. Call after
+    /// Board::hit_place returned Some so data holds the result.
+    pub fn record(
+        &mut self,
+        board_index: usize,
+        data: &[BoardType],
+        tile: Vec2,
+        won: bool,
+    ) {
+        let outcome = match data[get_tile_pos(tile.x as i32, tile.y as i32)] {
+            BoardType::Hit(ship) => {
+                let afloat = data.contains(&BoardType::Ship(ship));
+                match ShipExtent::find(data, ship) {
+                    Some(extent) if won => ShotOutcome::Won(extent),
+                    Some(extent) if !afloat => ShotOutcome::Sunk(extent),
+                    _ => ShotOutcome::Hit,
+                }
+            }
+            _ => ShotOutcome::Miss,
+        };
+
+        self.shots.push(Shot {
+            board: board_index,
+            tile: (tile.x as u32, tile.y as u32),
+            outcome,
+        });
+    }
+
+    /// Shots taken after the first count, for sending only what is new.
+    pub fn since(&self, count: usize) -> &[Shot] {
+        self.shots.get(count..).unwrap_or_default()
+    }
+
+    pub fn is_won(&self) -> bool {
+        matches!(
+            self.shots.last(),
+            Some(Shot {
+                outcome: ShotOutcome::Won(_),
+                ..
+            })
+        )
+    }
+}
+
+/// Follows a match from its MatchHistory without taking part in it. The
+/// first update catches up on every shot so far, so joining mid-game works
+/// the same as joining at the start. Boards it updates are made read only
+/// so a spectator can never fire.
+#[derive(Clone, Debug, Default)]
+pub struct Spectator {
+    /// Number of shots applied so far.
+    seen: usize,
+    /// Latest shots held back so the view trails the match. Everything
+    /// is shown once the match is won.
+    pub delay: usize,
+}
+
+impl Spectator {
+    pub fn new(delay: usize) -> Self {
+        Self { seen: 0, delay }
+    }
+
+    /// Shots not yet applied that are past the delay.
+    pub fn next_shots<'a>(&mut self, history: &'a MatchHistory) -> &'a [Shot] {
+        let end = if history.is_won() {
+            history.shots.len()
+        } else {
+            history.shots.len().saturating_sub(self.delay)
+        };
+        let count = end.saturating_sub(self.seen);
+        let shots = &history.since(self.seen)[..count];
+
+        self.seen += count;
+        shots
+    }
+
+    /// True once the winning shot was shown, after which both fleets can
+    /// be revealed.
+    pub fn is_finished(&self, history: &MatchHistory) -> bool {
+        history.is_won() && self.seen == history.shots.len()
+    }
+
+    /// Shows the new shots on boards, which should start empty as the
+    /// fleets are not known. Returns the shots shown so the caller can
+    /// update the turn and ship counters.
+    pub fn update<'a>(
+        &mut self,
+        history: &'a MatchHistory,
+        world: &mut World,
+        boards: &mut [Board],
+        resource: &TextureAllocation,
+        renderer: &mut GpuRenderer,
+        effects: &mut Effects,
+        seconds: f32,
+    ) -> &'a [Shot] {
+        boards.iter_mut().for_each(|board| board.read_only = true);
+
+        let shots = self.next_shots(history);
+        for shot in shots {
+            if let Some(board) = boards.get_mut(shot.board) {
+                board.show_shot(
+                    world, shot, resource, renderer, effects, seconds,
+                );
+            }
+        }
+        shots
+    }
+}
+
+/// Everything needed to set a match back up as it was, such as to
+/// reproduce a crash.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MatchSnapshot {
+    pub current_turn: i32,
+    pub got_winner: bool,
+    /// Tiles of each board including where the ships are.
+    pub boards: Vec<Vec<BoardType>>,
+    pub history: MatchHistory,
+}
+
+impl MatchSnapshot {
+    pub fn new(
+        gameboard: &GameBoard,
+        boards: &[Board],
+        history: &MatchHistory,
+    ) -> Self {
+        Self {
+            current_turn: gameboard.current_turn,
+            got_winner: gameboard.got_winner,
+            boards: boards.iter().map(|board| board.data.to_vec()).collect(),
+            history: history.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A board with a two tile ship standing at (3, 4) and a single tile
+    /// one at (0, 0).
+    fn fleet() -> [BoardType; 256] {
+        let mut data = [BoardType::None; 256];
+        data[get_tile_pos(3, 4)] = BoardType::Ship(1);
+        data[get_tile_pos(3, 5)] = BoardType::Ship(1);
+        data[get_tile_pos(0, 0)] = BoardType::Ship(0);
+        data
+    }
+
+    /// Fires at tile the way Board::hit_place does and records it.
+    fn fire(
+        history: &mut MatchHistory,
+        data: &mut [BoardType],
+        x: i32,
+        y: i32,
+    ) {
+        let index = get_tile_pos(x, y);
+        data[index] = match data[index] {
+            BoardType::Ship(ship) => BoardType::Hit(ship),
+            _ => BoardType::Missed,
+        };
+        let won = !data.iter().any(|tile| matches!(tile, BoardType::Ship(_)));
+        history.record(0, data, Vec2::new(x as f32, y as f32), won);
+    }
+
+    fn outcomes(history: &MatchHistory) -> Vec<ShotOutcome> {
+        history.shots.iter().map(|shot| shot.outcome).collect()
+    }
+
+    #[test]
+    fn sinking_records_the_ship_extent() {
+        let mut history = MatchHistory::default();
+        let mut data = fleet();
+        fire(&mut history, &mut data, 7, 7);
+        fire(&mut history, &mut data, 3, 5);
+        fire(&mut history, &mut data, 3, 4);
+        fire(&mut history, &mut data, 0, 0);
+
+        let standing = ShipExtent {
+            tile: (3, 4),
+            size: 2,
+            vertical: true,
+        };
+        let single = ShipExtent {
+            tile: (0, 0),
+            size: 1,
+            vertical: false,
+        };
+        assert_eq!(
+            outcomes(&history),
+            [
+                ShotOutcome::Miss,
+                ShotOutcome::Hit,
+                ShotOutcome::Sunk(standing),
+                ShotOutcome::Won(single),
+            ]
+        );
+        assert_eq!(standing.tiles().collect::<Vec<_>>(), [(3, 4), (3, 5)]);
+        assert!(history.is_won());
+    }
+
+    #[test]
+    fn joining_mid_game_catches_up_then_follows() {
+        let mut history = MatchHistory::default();
+        let mut data = fleet();
+        fire(&mut history, &mut data, 7, 7);
+        fire(&mut history, &mut data, 3, 5);
+
+        let mut spectator = Spectator::default();
+        let mut view = [BoardType::None; 256];
+        for shot in spectator.next_shots(&history) {
+            shot.apply(&mut view);
+        }
+        assert_eq!(view[get_tile_pos(7, 7)], BoardType::Missed);
+        assert_eq!(view[get_tile_pos(3, 5)], BoardType::Hit(UNKNOWN_SHIP));
+        assert_eq!(view[get_tile_pos(3, 4)], BoardType::None);
+        assert!(spectator.next_shots(&history).is_empty());
+
+        fire(&mut history, &mut data, 3, 4);
+        assert_eq!(spectator.next_shots(&history), history.since(2));
+        assert!(!spectator.is_finished(&history));
+    }
+
+    #[test]
+    fn delay_holds_back_shots_until_the_match_is_won() {
+        let mut history = MatchHistory::default();
+        let mut data = fleet();
+        let mut spectator = Spectator::new(2);
+
+        fire(&mut history, &mut data, 7, 7);
+        fire(&mut history, &mut data, 3, 5);
+        assert!(spectator.next_shots(&history).is_empty());
+
+        fire(&mut history, &mut data, 3, 4);
+        assert_eq!(spectator.next_shots(&history), &history.shots[..1]);
+
+        fire(&mut history, &mut data, 0, 0);
+        assert_eq!(spectator.next_shots(&history), history.since(1));
+        assert!(spectator.is_finished(&history));
+    }
+}
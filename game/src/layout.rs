@@ -0,0 +1,5 @@
+mod node;
+mod screen;
+
+pub use node::*;
+pub use screen::*;
@@ -0,0 +1,139 @@
+use graphics::*;
+
+/// The point on a parent the Node is attached to. The same point on the
+/// Node itself is used as its pivot, so a TopRight Node with a negative
+/// offset stays inside the top right corner of its parent.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Anchor {
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+    CenterLeft,
+    Center,
+    CenterRight,
+    TopLeft,
+    TopCenter,
+    TopRight,
+}
+
+impl Anchor {
+    /// Returns the anchor as a fraction of the width and height.
+    /// Y goes up so bottom is 0.0 and top is 1.0.
+    pub fn factor(&self) -> Vec2 {
+        match self {
+            Anchor::BottomLeft => Vec2::new(0.0, 0.0),
+            Anchor::BottomCenter => Vec2::new(0.5, 0.0),
+            Anchor::BottomRight => Vec2::new(1.0, 0.0),
+            Anchor::CenterLeft => Vec2::new(0.0, 0.5),
+            Anchor::Center => Vec2::new(0.5, 0.5),
+            Anchor::CenterRight => Vec2::new(1.0, 0.5),
+            Anchor::TopLeft => Vec2::new(0.0, 1.0),
+            Anchor::TopCenter => Vec2::new(0.5, 1.0),
+            Anchor::TopRight => Vec2::new(1.0, 1.0),
+        }
+    }
+}
+
+/// A resolved area. pos is the bottom left corner.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Rect {
+    pub pos: Vec2,
+    pub size: Vec2,
+}
+
+impl Rect {
+    pub fn new(pos: Vec2, size: Vec2) -> Self {
+        Self { pos, size }
+    }
+
+    pub fn right(&self) -> f32 {
+        self.pos.x + self.size.x
+    }
+
+    pub fn top(&self) -> f32 {
+        self.pos.y + self.size.y
+    }
+
+    /// Converts the Rect into another unit, such as from sprite units into
+    /// screen pixels for the Text that does not use the camera.
+    pub fn scale(&self, scale: f32) -> Rect {
+        Rect::new(self.pos * scale, self.size * scale)
+    }
+
+    pub fn bounds(&self) -> Bounds {
+        Bounds::new(self.pos.x, self.pos.y, self.right(), self.top())
+    }
+
+    pub fn contains(&self, pos: Vec2) -> bool {
+        pos.x >= self.pos.x
+            && pos.x <= self.right()
+            && pos.y >= self.pos.y
+            && pos.y <= self.top()
+    }
+}
+
+/// Describes where something sits within its parent.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Node {
+    pub anchor: Anchor,
+    /// offset from the anchor point of the parent.
+    pub offset: Vec2,
+    pub size: Vec2,
+}
+
+impl Node {
+    pub const fn new(anchor: Anchor, offset: Vec2, size: Vec2) -> Self {
+        Self {
+            anchor,
+            offset,
+            size,
+        }
+    }
+
+    pub fn resolve(&self, parent: &Rect) -> Rect {
+        let factor = self.anchor.factor();
+        let pos = parent.pos + parent.size * factor + self.offset
+            - self.size * factor;
+
+        Rect::new(pos, self.size)
+    }
+}
+
+/// A Text placed within a Node. The Node is used as the clipping bounds
+/// and the text starts at inset from the Node's bottom left corner.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TextNode {
+    pub node: Node,
+    pub inset: Vec2,
+}
+
+impl TextNode {
+    pub const fn new(node: Node, inset: Vec2) -> Self {
+        Self { node, inset }
+    }
+
+    pub fn resolve(&self, parent: &Rect) -> TextRect {
+        let bounds = self.node.resolve(parent);
+
+        TextRect {
+            pos: bounds.pos + self.inset,
+            bounds,
+        }
+    }
+}
+
+/// Resolved position and clipping area of a Text.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct TextRect {
+    pub pos: Vec2,
+    pub bounds: Rect,
+}
+
+impl TextRect {
+    pub fn scale(&self, scale: f32) -> TextRect {
+        TextRect {
+            pos: self.pos * scale,
+            bounds: self.bounds.scale(scale),
+        }
+    }
+}
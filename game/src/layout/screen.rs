@@ -0,0 +1,273 @@
+use cosmic_text::Metrics;
+use graphics::*;
+use serde::{Deserialize, Serialize};
+use winit::dpi::PhysicalSize;
+
+use super::{Anchor, Node, Rect, TextNode, TextRect};
+use crate::FONT_SIZE;
+
+// All Nodes are in sprite units which is the screen size divided by the zoom.
+// Y goes up from the bottom of the screen.
+
+/// The game background. Everything but the fps text sits within it.
+pub const PANEL: Node =
+    Node::new(Anchor::Center, Vec2::ZERO, Vec2::new(548.0, 360.0));
+
+/// Boards within the panel. 12 tiles of 20 each.
+pub const BOARDS: [Node; 2] = [
+    Node::new(
+        Anchor::BottomLeft,
+        Vec2::new(27.0, 11.0),
+        Vec2::new(240.0, 240.0),
+    ),
+    Node::new(
+        Anchor::BottomLeft,
+        Vec2::new(297.0, 11.0),
+        Vec2::new(240.0, 240.0),
+    ),
+];
+
+/// Win or Lose image within its board.
+pub const RESULT: Node =
+    Node::new(Anchor::Center, Vec2::new(0.0, 1.0), Vec2::new(240.0, 44.0));
+
+/// Ship counters within the panel, ordered by ship size.
+pub const SHIP_COUNTERS: [[TextNode; 4]; 2] = [
+    [
+        counter_node(34.0, 326.0),
+        counter_node(34.0, 301.0),
+        counter_node(144.0, 326.0),
+        counter_node(144.0, 301.0),
+    ],
+    [
+        counter_node(303.0, 326.0),
+        counter_node(303.0, 301.0),
+        counter_node(413.0, 326.0),
+        counter_node(413.0, 301.0),
+    ],
+];
+
+/// Turn and status message within the panel.
+pub const STATUS_TEXT: TextNode = TextNode::new(
+    Node::new(
+        Anchor::BottomLeft,
+        Vec2::new(174.0, 0.0),
+        Vec2::new(199.0, 10.0),
+    ),
+    Vec2::new(71.0, 2.0),
+);
+
+/// Frame rate and frame time stats within the screen.
+pub const FPS_TEXT: TextNode = TextNode::new(
+    Node::new(Anchor::BottomLeft, Vec2::ZERO, Vec2::new(160.0, 10.5)),
+    Vec2::new(2.5, 2.5),
+);
+
+/// Options menu within the panel.
+pub const OPTIONS: Node =
+    Node::new(Anchor::Center, Vec2::ZERO, Vec2::new(200.0, 120.0));
+
+/// Options menu title within the options menu.
+pub const OPTIONS_TITLE: TextNode = TextNode::new(
+    Node::new(
+        Anchor::TopCenter,
+        Vec2::new(0.0, -6.0),
+        Vec2::new(184.0, 12.0),
+    ),
+    Vec2::new(4.0, 2.0),
+);
+
+/// Rows of the options menu from the top down.
+pub const OPTIONS_ROWS: [TextNode; 6] = [
+    options_row(-26.0),
+    options_row(-41.0),
+    options_row(-56.0),
+    options_row(-71.0),
+    options_row(-86.0),
+    options_row(-101.0),
+];
+
+/// Chat panel within the panel, over the bottom of the boards.
+pub const CHAT: Node = Node::new(
+    Anchor::BottomCenter,
+    Vec2::new(0.0, 16.0),
+    Vec2::new(300.0, 100.0),
+);
+
+/// Scrollback of the chat panel.
+pub const CHAT_LOG: TextNode = TextNode::new(
+    Node::new(
+        Anchor::TopCenter,
+        Vec2::new(0.0, -4.0),
+        Vec2::new(292.0, 74.0),
+    ),
+    Vec2::new(2.0, 0.0),
+);
+
+/// Line being typed into the chat panel.
+pub const CHAT_INPUT: TextNode = TextNode::new(
+    Node::new(
+        Anchor::BottomCenter,
+        Vec2::new(0.0, 4.0),
+        Vec2::new(292.0, 12.0),
+    ),
+    Vec2::new(2.0, 2.0),
+);
+
+const fn counter_node(x: f32, y: f32) -> TextNode {
+    TextNode::new(
+        Node::new(Anchor::BottomLeft, Vec2::new(x, y), Vec2::new(27.0, 15.0)),
+        Vec2::new(12.0, 0.0),
+    )
+}
+
+const fn options_row(y: f32) -> TextNode {
+    TextNode::new(
+        Node::new(Anchor::TopCenter, Vec2::new(0.0, y), Vec2::new(184.0, 12.0)),
+        Vec2::new(4.0, 2.0),
+    )
+}
+
+/// How the zoom is picked to fit the panel into the screen.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ZoomMode {
+    /// Whole numbers only so every sprite pixel is the same size. Falls
+    /// back to Fractional if the screen is smaller than the panel.
+    Integer,
+    /// Fills as much of the screen as it can.
+    Fractional,
+}
+
+impl ZoomMode {
+    pub fn fit(&self, screen_size: &PhysicalSize<f32>) -> f32 {
+        let zoom = (screen_size.width / PANEL.size.x)
+            .min(screen_size.height / PANEL.size.y);
+
+        match self {
+            ZoomMode::Integer if zoom >= 1.0 => zoom.floor(),
+            _ => zoom,
+        }
+    }
+}
+
+/// Every position the game needs, resolved for a screen size and zoom.
+/// Rects are in sprite units for Images and Maps. TextRects are already
+/// scaled into screen pixels since Text does not use the camera.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ScreenLayout {
+    pub zoom: f32,
+    pub screen: Rect,
+    pub panel: Rect,
+    pub boards: [Rect; 2],
+    pub results: [Rect; 2],
+    pub ship_counter: [[TextRect; 4]; 2],
+    pub status_text: TextRect,
+    pub fps_text: TextRect,
+    pub options: Rect,
+    pub options_title: TextRect,
+    pub options_rows: [TextRect; 6],
+    pub chat: Rect,
+    pub chat_log: TextRect,
+    pub chat_input: TextRect,
+    /// Multiplies the font size, see Accessibility.
+    pub text_scale: f32,
+}
+
+impl ScreenLayout {
+    pub fn new(screen_size: &PhysicalSize<f32>, zoom: f32) -> Self {
+        let screen = Rect::new(
+            Vec2::ZERO,
+            Vec2::new(screen_size.width, screen_size.height) / zoom,
+        );
+        let mut panel = PANEL.resolve(&screen);
+        // Keep the panel on whole pixels so the sprites stay sharp.
+        panel.pos = (panel.pos * zoom).floor() / zoom;
+        let boards = BOARDS.map(|node| node.resolve(&panel));
+        let results = boards.map(|board| RESULT.resolve(&board));
+        let ship_counter = SHIP_COUNTERS.map(|counters| {
+            counters.map(|node| node.resolve(&panel).scale(zoom))
+        });
+        let options = OPTIONS.resolve(&panel);
+        let chat = CHAT.resolve(&panel);
+
+        Self {
+            zoom,
+            screen,
+            panel,
+            boards,
+            results,
+            ship_counter,
+            status_text: STATUS_TEXT.resolve(&panel).scale(zoom),
+            fps_text: FPS_TEXT.resolve(&screen).scale(zoom),
+            options,
+            options_title: OPTIONS_TITLE.resolve(&options).scale(zoom),
+            options_rows: OPTIONS_ROWS
+                .map(|node| node.resolve(&options).scale(zoom)),
+            chat,
+            chat_log: CHAT_LOG.resolve(&chat).scale(zoom),
+            chat_input: CHAT_INPUT.resolve(&chat).scale(zoom),
+            text_scale: 1.0,
+        }
+    }
+
+    /// Picks the zoom for the screen size then lays everything out with it.
+    pub fn fit(screen_size: &PhysicalSize<f32>, mode: ZoomMode) -> Self {
+        Self::new(screen_size, mode.fit(screen_size))
+    }
+
+    pub fn with_text_scale(mut self, text_scale: f32) -> Self {
+        self.text_scale = text_scale;
+        self
+    }
+
+    /// Font metrics so Text stays the same size as the sprites around it.
+    pub fn font_metrics(&self) -> Metrics {
+        Metrics::new(FONT_SIZE, FONT_SIZE).scale(self.zoom * self.text_scale)
+    }
+
+    /// Moves the Text into rect and resizes its font for the current zoom.
+    pub fn place_text(
+        &self,
+        text: &mut Text,
+        rect: &TextRect,
+        renderer: &mut GpuRenderer,
+    ) {
+        let screen = self.screen.scale(self.zoom);
+        let metrics = self.font_metrics();
+        let pos = Vec3::new(rect.pos.x, rect.pos.y, text.pos.z);
+        // Scaled up text grows upwards past its Node so it is not clipped.
+        let mut bounds = rect.bounds;
+        bounds.size.y += metrics.line_height - FONT_SIZE * self.zoom;
+
+        text.set_metrics(renderer, metrics)
+            .set_buffer_size(
+                renderer,
+                screen.size.x as i32,
+                screen.size.y as i32,
+            )
+            .set_position(pos)
+            .set_bounds(Some(bounds.bounds()));
+        text.size.y = metrics.line_height;
+    }
+
+    /// Like place_text but for Text with many lines. The lines start at the
+    /// top of rect and scroll within it.
+    pub fn place_text_area(
+        &self,
+        text: &mut Text,
+        rect: &TextRect,
+        renderer: &mut GpuRenderer,
+    ) {
+        let metrics = self.font_metrics();
+        let pos = Vec3::new(rect.pos.x, rect.pos.y, text.pos.z);
+        let width = rect.bounds.right() - rect.pos.x;
+        let height = rect.bounds.top() - rect.pos.y;
+
+        text.set_metrics(renderer, metrics)
+            .set_buffer_size(renderer, width as i32, height as i32)
+            .set_position(pos)
+            .set_bounds(Some(rect.bounds.bounds()));
+        text.size = Vec2::new(width, height);
+    }
+}
@@ -0,0 +1,229 @@
+use graphics::*;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt::Display, path::Path};
+
+use crate::func::{load_toml, load_toml_dir};
+use crate::theme::FontFile;
+
+/// Which plural form a count uses. Each language maps counts onto these
+/// differently, see Locale::plural_category.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PluralCategory {
+    Zero,
+    One,
+    Two,
+    Few,
+    Many,
+    Other,
+}
+
+/// Every form is optional but other which is used when the category the
+/// count falls into was not given.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PluralForms {
+    pub zero: Option<String>,
+    pub one: Option<String>,
+    pub two: Option<String>,
+    pub few: Option<String>,
+    pub many: Option<String>,
+    pub other: String,
+}
+
+impl PluralForms {
+    pub fn get(&self, category: PluralCategory) -> &str {
+        let form = match category {
+            PluralCategory::Zero => &self.zero,
+            PluralCategory::One => &self.one,
+            PluralCategory::Two => &self.two,
+            PluralCategory::Few => &self.few,
+            PluralCategory::Many => &self.many,
+            PluralCategory::Other => return &self.other,
+        };
+
+        form.as_deref().unwrap_or(&self.other)
+    }
+}
+
+/// A message is either a plain string or a table of plural forms.
+/// Both can hold placeholders such as {player} or {count}.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Message {
+    Text(String),
+    Plural(PluralForms),
+}
+
+/// Message catalog for one language.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Locale {
+    /// Shown in the options menu, so it should be in its own language.
+    pub name: String,
+    /// Language code such as en or ru. Picks the plural rules.
+    pub code: String,
+    /// Fonts loaded into the font system so scripts the theme font lacks
+    /// can fall back to them.
+    #[serde(default)]
+    pub fonts: Vec<FontFile>,
+    pub messages: HashMap<String, Message>,
+}
+
+impl Locale {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AscendingError> {
+        load_toml(path)
+    }
+
+    /// Loads every locale within the folder sorted by file name. Locales
+    /// that fail to load are logged and skipped.
+    pub fn load_all(
+        dir: impl AsRef<Path>,
+    ) -> Result<Vec<Self>, AscendingError> {
+        load_toml_dir(dir)
+    }
+
+    /// Loads the fallback fonts. cosmic text falls back through every font
+    /// in the font system when a glyph is missing from the one asked for.
+    /// Fonts that fail to load are logged so the system fonts can still be
+    /// tried.
+    pub fn load_fonts(&self, renderer: &mut GpuRenderer) {
+        for font in &self.fonts {
+            if let Err(e) = font.load(renderer) {
+                warn!("failed to load font {}: {}", font.path, e);
+            }
+        }
+    }
+
+    pub fn plural_category(&self, count: i64) -> PluralCategory {
+        let language = self.code.split(['-', '_']).next().unwrap_or_default();
+        let n = count.unsigned_abs();
+
+        match language {
+            "ja" | "ko" | "zh" => PluralCategory::Other,
+            "ru" | "uk" => match (n % 10, n % 100) {
+                (1, rem) if rem != 11 => PluralCategory::One,
+                (2..=4, rem) if !(12..=14).contains(&rem) => {
+                    PluralCategory::Few
+                }
+                _ => PluralCategory::Many,
+            },
+            _ if n == 1 => PluralCategory::One,
+            _ => PluralCategory::Other,
+        }
+    }
+
+    /// Looks up key and fills in its placeholders. Missing keys are logged
+    /// and the key itself is returned so it still shows up on screen.
+    pub fn text(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        match self.messages.get(key) {
+            Some(Message::Text(text)) => format_message(text, args),
+            Some(Message::Plural(forms)) => format_message(&forms.other, args),
+            None => self.missing(key),
+        }
+    }
+
+    /// Like text but picks the plural form for count. count is also
+    /// available to the message as {count}.
+    pub fn plural(
+        &self,
+        key: &str,
+        count: i64,
+        args: &[(&str, &dyn Display)],
+    ) -> String {
+        let form = match self.messages.get(key) {
+            Some(Message::Plural(forms)) => {
+                forms.get(self.plural_category(count))
+            }
+            Some(Message::Text(text)) => text,
+            None => return self.missing(key),
+        };
+
+        let mut all_args: Vec<(&str, &dyn Display)> = vec![("count", &count)];
+        all_args.extend_from_slice(args);
+        format_message(form, &all_args)
+    }
+
+    fn missing(&self, key: &str) -> String {
+        warn!("locale {} is missing message {}", self.code, key);
+        key.to_owned()
+    }
+}
+
+/// Replaces each {name} within message with its argument.
+fn format_message(message: &str, args: &[(&str, &dyn Display)]) -> String {
+    args.iter()
+        .fold(message.to_owned(), |message, (name, value)| {
+            message.replace(&format!("{{{}}}", name), &value.to_string())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locale(code: &str) -> Locale {
+        let mut locale: Locale = toml::from_str(
+            r#"
+            name = "Test"
+            code = "en"
+
+            [messages]
+            fps = "FPS: {fps}"
+
+            [messages.ships]
+            one = "{count} ship"
+            few = "{count} ships (few)"
+            many = "{count} ships (many)"
+            other = "{count} ships"
+            "#,
+        )
+        .unwrap();
+        locale.code = code.to_owned();
+        locale
+    }
+
+    #[test]
+    fn russian_plurals_follow_the_last_digits() {
+        let ru = locale("ru");
+        let categories: Vec<_> = [1, 2, 5, 11, 21, 22, 112]
+            .into_iter()
+            .map(|count| ru.plural_category(count))
+            .collect();
+
+        assert_eq!(
+            categories,
+            [
+                PluralCategory::One,
+                PluralCategory::Few,
+                PluralCategory::Many,
+                PluralCategory::Many,
+                PluralCategory::One,
+                PluralCategory::Few,
+                PluralCategory::Many,
+            ]
+        );
+        assert_eq!(ru.plural("ships", 21, &[]), "21 ship");
+        assert_eq!(ru.plural("ships", 11, &[]), "11 ships (many)");
+        assert_eq!(locale("uk-UA").plural_category(3), PluralCategory::Few);
+    }
+
+    #[test]
+    fn other_languages_use_their_own_rules() {
+        let ja = locale("ja");
+        assert_eq!(ja.plural_category(1), PluralCategory::Other);
+        assert_eq!(ja.plural("ships", 1, &[]), "1 ships");
+
+        let en = locale("en");
+        assert_eq!(en.plural_category(1), PluralCategory::One);
+        assert_eq!(en.plural_category(-1), PluralCategory::One);
+        assert_eq!(en.plural_category(0), PluralCategory::Other);
+        assert_eq!(en.plural("ships", 2, &[]), "2 ships");
+    }
+
+    #[test]
+    fn missing_messages_show_their_key() {
+        let en = locale("en");
+        assert_eq!(en.text("fps", &[("fps", &60)]), "FPS: 60");
+        assert_eq!(en.text("status-turn", &[("player", &1)]), "status-turn");
+        assert_eq!(en.plural("status-winner", 2, &[]), "status-winner");
+    }
+}
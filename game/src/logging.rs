@@ -0,0 +1,287 @@
+mod crash;
+
+pub use crash::*;
+
+use log::{LevelFilter, Metadata, Record};
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use crate::settings::LogSettings;
+
+/// Logs to stdout and a log file with a timestamp and the module of each
+/// line. Modules can be given their own level.
+pub struct GameLogger {
+    default: LevelFilter,
+    /// Sorted longest first so the most specific module is found first.
+    modules: Vec<(String, LevelFilter)>,
+    file: Mutex<Option<RotatingFile>>,
+}
+
+impl GameLogger {
+    /// Settings must have been validated so every level parses. If the log
+    /// file can not be opened the game still logs to stdout.
+    pub fn new(settings: &LogSettings) -> Self {
+        let mut modules = settings.module_filters().unwrap_or_default();
+        modules.sort_by(|a, b| b.0.len().cmp(&a.0.len()));
+
+        let file = RotatingFile::open(
+            &settings.file,
+            settings.max_file_kb * 1024,
+            settings.max_files,
+        )
+        .map_err(|e| {
+            eprintln!(
+                "failed to open log file {}: {}",
+                settings.file.display(),
+                e
+            )
+        })
+        .ok();
+
+        Self {
+            default: settings.level_filter().unwrap_or(LevelFilter::Info),
+            modules,
+            file: Mutex::new(file),
+        }
+    }
+
+    /// Installs the logger for the log macros. Can only be done once.
+    pub fn install(self) {
+        let max_level = self.max_level();
+        let logger: &'static GameLogger = Box::leak(Box::new(self));
+
+        if log::set_logger(logger).is_ok() {
+            log::set_max_level(max_level);
+        }
+    }
+
+    /// Most verbose level any module logs at.
+    pub fn max_level(&self) -> LevelFilter {
+        self.modules
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.default, Ord::max)
+    }
+
+    fn level_for(&self, target: &str) -> LevelFilter {
+        self.modules
+            .iter()
+            .find(|(module, _)| {
+                target == module
+                    || target
+                        .strip_prefix(module.as_str())
+                        .map_or(false, |rest| rest.starts_with("::"))
+            })
+            .map_or(self.default, |(_, level)| *level)
+    }
+}
+
+impl log::Log for GameLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level_for(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let line = format!(
+            "{} {:<5} {} - {}\n",
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+        print!("{}", line);
+
+        if let Ok(mut file) = self.file.lock() {
+            if let Some(file) = file.as_mut() {
+                let _ = file.write(line.as_bytes());
+            }
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            if let Some(file) = file.as_mut() {
+                let _ = file.file.flush();
+            }
+        }
+    }
+}
+
+/// Appends to a file until it grows past max_size, then moves it to
+/// name.1 and starts over. Older files move up one number each time and
+/// only max_files of them are kept.
+pub struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_size: u64,
+    max_files: usize,
+}
+
+impl RotatingFile {
+    pub fn open(
+        path: impl AsRef<Path>,
+        max_size: u64,
+        max_files: usize,
+    ) -> std::io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let file = File::options().append(true).create(true).open(&path)?;
+        let size = file.metadata()?.len();
+
+        Ok(Self {
+            path,
+            file,
+            size,
+            max_size,
+            max_files,
+        })
+    }
+
+    pub fn write(&mut self, data: &[u8]) -> std::io::Result<()> {
+        if self.size > 0 && self.size + data.len() as u64 > self.max_size {
+            self.rotate()?;
+        }
+
+        self.file.write_all(data)?;
+        self.size += data.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        self.file.flush()?;
+
+        if self.max_files > 0 {
+            // Windows will not rename over a file so the oldest is removed first.
+            let _ = fs::remove_file(self.numbered(self.max_files));
+            for index in (1..self.max_files).rev() {
+                let _ =
+                    fs::rename(self.numbered(index), self.numbered(index + 1));
+            }
+            fs::rename(&self.path, self.numbered(1))?;
+        }
+
+        self.file = File::options()
+            .write(true)
+            .truncate(true)
+            .create(true)
+            .open(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+
+    fn numbered(&self, index: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", index));
+        PathBuf::from(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log::{Level, Log};
+
+    fn logger(level: &str, modules: &[(&str, &str)]) -> GameLogger {
+        let settings = LogSettings {
+            level: level.to_owned(),
+            modules: modules
+                .iter()
+                .map(|(module, level)| (module.to_string(), level.to_string()))
+                .collect(),
+            file: std::env::temp_dir().join(format!(
+                "game_log_{}_{}.log",
+                level,
+                std::process::id()
+            )),
+            ..Default::default()
+        };
+        let logger = GameLogger::new(&settings);
+        // Only the filters are tested so the file is not needed.
+        let _ = fs::remove_file(&settings.file);
+        logger
+    }
+
+    fn enabled(logger: &GameLogger, target: &str, level: Level) -> bool {
+        logger.enabled(&Metadata::builder().target(target).level(level).build())
+    }
+
+    #[test]
+    fn levels_parse() {
+        let mut settings = LogSettings {
+            level: "debug".to_owned(),
+            ..Default::default()
+        };
+        assert_eq!(settings.level_filter().unwrap(), LevelFilter::Debug);
+        settings.level = "OFF".to_owned();
+        assert_eq!(settings.level_filter().unwrap(), LevelFilter::Off);
+        settings.level = "loud".to_owned();
+        assert!(settings.level_filter().is_err());
+
+        settings
+            .modules
+            .insert("game::audio".to_owned(), "trace".to_owned());
+        assert_eq!(
+            settings.module_filters().unwrap(),
+            [("game::audio".to_owned(), LevelFilter::Trace)]
+        );
+        settings
+            .modules
+            .insert("game::board".to_owned(), "noisy".to_owned());
+        assert!(settings.module_filters().is_err());
+    }
+
+    #[test]
+    fn most_specific_module_filters() {
+        let logger =
+            logger("info", &[("game", "warn"), ("game::audio", "trace")]);
+
+        assert_eq!(logger.max_level(), LevelFilter::Trace);
+        assert!(enabled(&logger, "wgpu_core", Level::Info));
+        assert!(!enabled(&logger, "wgpu_core", Level::Debug));
+        assert!(enabled(&logger, "game", Level::Warn));
+        assert!(!enabled(&logger, "game::board", Level::Info));
+        assert!(enabled(&logger, "game::audio::music", Level::Trace));
+        // Only whole module names match.
+        assert!(!enabled(&logger, "game::audiox", Level::Info));
+        assert!(enabled(&logger, "gamepad", Level::Info));
+    }
+
+    #[test]
+    fn log_file_rotates() {
+        let path = std::env::temp_dir()
+            .join(format!("game_rotate_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        let log = path.join("game.log");
+        let mut file = RotatingFile::open(&log, 8, 2).unwrap();
+
+        for line in ["first\n", "second\n", "third\n", "fourth\n"] {
+            file.write(line.as_bytes()).unwrap();
+        }
+
+        assert_eq!(fs::read_to_string(&log).unwrap(), "fourth\n");
+        assert_eq!(
+            fs::read_to_string(path.join("game.log.1")).unwrap(),
+            "third\n"
+        );
+        assert_eq!(
+            fs::read_to_string(path.join("game.log.2")).unwrap(),
+            "second\n"
+        );
+        assert!(!path.join("game.log.3").exists());
+
+        fs::remove_dir_all(&path).unwrap();
+    }
+}
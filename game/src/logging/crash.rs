@@ -0,0 +1,149 @@
+use backtrace::Backtrace;
+use log::error;
+use std::{
+    fs, panic,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use crate::history::MatchSnapshot;
+use crate::settings::Settings;
+
+/// What goes into a crash report besides the panic itself. Everything is
+/// turned into text ahead of time so the panic hook has as little work to
+/// do as it can.
+struct CrashContext {
+    adapter: Option<String>,
+    settings: Option<String>,
+    snapshot: Option<String>,
+}
+
+static CRASH_CONTEXT: Mutex<CrashContext> = Mutex::new(CrashContext {
+    adapter: None,
+    settings: None,
+    snapshot: None,
+});
+
+fn with_context(f: impl FnOnce(&mut CrashContext)) {
+    let mut context = CRASH_CONTEXT.lock().unwrap_or_else(|e| e.into_inner());
+    f(&mut context);
+}
+
+/// Records which GPU and driver the game ended up using.
+pub fn set_crash_adapter(info: &wgpu::AdapterInfo) {
+    let adapter = format!("{:#?}\n", info);
+    with_context(|context| context.adapter = Some(adapter));
+}
+
+/// Records the settings the game is running with.
+pub fn set_crash_settings(settings: &Settings) {
+    let settings = toml::to_string_pretty(settings).ok();
+    with_context(|context| context.settings = settings);
+}
+
+/// Records the state of the match. Call whenever it changes so the report
+/// holds the last state before the crash.
+pub fn set_crash_snapshot(snapshot: &MatchSnapshot) {
+    let snapshot = toml::to_string_pretty(snapshot).ok();
+    with_context(|context| context.snapshot = snapshot);
+}
+
+/// Writes a crash report into a new folder within dir on every panic.
+pub fn install_crash_reporter(dir: PathBuf) {
+    panic::set_hook(Box::new(move |panic_info| {
+        let backtrace = Backtrace::new();
+
+        match write_crash_report(&dir, &panic_info.to_string(), &backtrace) {
+            Ok(path) => error!(
+                "PANIC: {}, report written to {}",
+                panic_info,
+                path.display()
+            ),
+            Err(e) => error!(
+                "PANIC: {}, BACKTRACE: {:?}, failed to write report: {}",
+                panic_info, backtrace, e
+            ),
+        }
+        log::logger().flush();
+    }));
+}
+
+/// Folder holding backtrace.txt, adapter.txt, settings.toml and match.toml.
+/// Files for anything not recorded yet are left out.
+fn write_crash_report(
+    dir: &Path,
+    message: &str,
+    backtrace: &Backtrace,
+) -> std::io::Result<PathBuf> {
+    let path =
+        dir.join(chrono::Local::now().format("%Y%m%d-%H%M%S%.3f").to_string());
+    fs::create_dir_all(&path)?;
+
+    fs::write(
+        path.join("backtrace.txt"),
+        format!("{}\n\n{:?}", message, backtrace),
+    )?;
+
+    let context = CRASH_CONTEXT.lock().unwrap_or_else(|e| e.into_inner());
+    for (name, data) in [
+        ("adapter.txt", &context.adapter),
+        ("settings.toml", &context.settings),
+        ("match.toml", &context.snapshot),
+    ] {
+        if let Some(data) = data {
+            fs::write(path.join(name), data)?;
+        }
+    }
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::BoardType;
+    use crate::history::{MatchHistory, Shot, ShotOutcome};
+
+    #[test]
+    fn report_holds_the_recorded_context() {
+        let snapshot = MatchSnapshot {
+            current_turn: 1,
+            got_winner: false,
+            boards: vec![
+                vec![BoardType::None, BoardType::Ship(2)],
+                vec![BoardType::Hit(0), BoardType::Missed],
+            ],
+            history: MatchHistory {
+                shots: vec![Shot {
+                    board: 1,
+                    tile: (3, 4),
+                    outcome: ShotOutcome::Hit,
+                }],
+            },
+        };
+        set_crash_settings(&Settings::default());
+        set_crash_snapshot(&snapshot);
+
+        let dir = std::env::temp_dir()
+            .join(format!("game_crash_reports_{}", std::process::id()));
+        let path =
+            write_crash_report(&dir, "boom", &Backtrace::new_unresolved())
+                .unwrap();
+        let read = |name: &str| fs::read_to_string(path.join(name));
+
+        assert!(read("backtrace.txt").unwrap().starts_with("boom\n\n"));
+        // No adapter was recorded so its file is left out.
+        assert!(read("adapter.txt").is_err());
+        let settings: Settings =
+            toml::from_str(&read("settings.toml").unwrap()).unwrap();
+        assert_eq!(
+            toml::to_string_pretty(&settings).unwrap(),
+            toml::to_string_pretty(&Settings::default()).unwrap()
+        );
+        let loaded: MatchSnapshot =
+            toml::from_str(&read("match.toml").unwrap()).unwrap();
+        assert_eq!(loaded, snapshot);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
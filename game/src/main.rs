@@ -1,5 +1,4 @@
 #![allow(dead_code, clippy::collapsible_match, unused_imports)]
-use backtrace::Backtrace;
 use camera::{
     controls::{Controls, FlatControls, FlatSettings},
     Projection,
@@ -9,8 +8,10 @@ use ::futures::SinkExt;
 use glam::vec4;
 use graphics::{ *};
 use hecs::World;
-use input::{Bindings, FrameTime, InputHandler};
-use log::{error, info, warn, Level, LevelFilter, Metadata, Record};
+use input::{Bindings, Consume, FrameTime, GamepadSource, InputContext, InputEvent, InputHandler, InputPlayback, InputRecorder, InputRecording, Timer};
+#[cfg(feature = "gilrs")]
+use input::GilrsSource;
+use log::{error, info, warn};
 use naga::{front::wgsl, valid::Validator};
 use serde::{Deserialize, Serialize};
 use std::{
@@ -23,9 +24,9 @@ use std::{
     rc::Rc,
     time::Duration,
 };
-use wgpu::{Backends, Dx12Compiler, InstanceDescriptor, InstanceFlags};
+use wgpu::{Dx12Compiler, InstanceDescriptor, InstanceFlags};
 use winit::{
-    dpi::PhysicalSize,
+    dpi::{LogicalSize, PhysicalPosition, PhysicalSize},
     event::*,
     event_loop::{ControlFlow, EventLoop},
     window::WindowBuilder,
@@ -48,17 +49,41 @@ mod ui;
 mod func;
 mod board;
 mod collection;
+mod layout;
+mod theme;
+mod locale;
+mod accessibility;
+mod audio;
+mod history;
+mod settings;
+mod logging;
+mod ecs;
 
 use gamestate::*;
 use board::*;
-use board::Animation;
 use func::*;
 use collection::*;
+use layout::*;
+use theme::*;
+use locale::*;
+use accessibility::*;
+use audio::*;
+use history::*;
+use settings::*;
+use logging::*;
+use ecs::*;
+use ui::*;
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
 enum Action {
     Quit,
     Select,
+    Options,
+    Prev,
+    Next,
+    Up,
+    Down,
+    Chat,
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
@@ -69,60 +94,71 @@ enum Axis {
     Pitch,
 }
 
-// creates a static global logger type for setting the logger
-static MY_LOGGER: MyLogger = MyLogger(Level::Debug);
-
-struct MyLogger(pub Level);
-
-impl log::Log for MyLogger {
-    // checks if it can log these types of events.
-    fn enabled(&self, metadata: &Metadata) -> bool {
-        metadata.level() <= self.0
+/// Index of the item named name or the first one if none match.
+fn find_or_first<T>(items: &[T], kind: &str, name: &str, item_name: impl Fn(&T) -> &String) -> usize {
+    if name.is_empty() {
+        return 0;
     }
 
-    // This logs to a panic file. This is so we can see
-    // Errors and such if a program crashes in full render mode.
-    fn log(&self, record: &Record) {
-        if self.enabled(record.metadata()) {
-            let msg = format!("{} - {}\n", record.level(), record.args());
-            println!("{}", &msg);
-
-            let mut file = match File::options()
-                .append(true)
-                .create(true)
-                .open("paniclog.txt")
-            {
-                Ok(v) => v,
-                Err(_) => return,
-            };
-
-            let _ = file.write(msg.as_bytes());
+    items.iter().position(|item| item_name(item) == name).unwrap_or_else(|| {
+        warn!("{} {} not found, using {}", kind, name, item_name(&items[0]));
+        0
+    })
+}
+
+/// Real gamepads, or None when they can not be read.
+#[cfg(feature = "gilrs")]
+fn open_gamepads() -> Option<Box<dyn GamepadSource>> {
+    match GilrsSource::new() {
+        Ok(gamepads) => Some(Box::new(gamepads)),
+        Err(e) => {
+            warn!("{}", e);
+            None
         }
     }
-    fn flush(&self) {}
 }
 
-fn action_index(action: Action) -> usize {
-    match action {
-        Action::Quit => 0,
-        Action::Select => 1,
-    }
+/// Built without the gilrs feature so there are no gamepads to read.
+#[cfg(not(feature = "gilrs"))]
+fn open_gamepads() -> Option<Box<dyn GamepadSource>> {
+    None
 }
 
 #[tokio::main]
 async fn main() -> Result<(), AscendingError> {
-    // Create logger to output to a File
-    log::set_logger(&MY_LOGGER).unwrap();
-    // Set the Max level we accept logging to the file for.
-    log::set_max_level(LevelFilter::Info);
+    let args = match CliArgs::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
+    if args.help {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    // stored is what gets saved back to the file so options from the command line do not stick.
+    // Nothing is logged yet since the settings say how logging is set up.
+    let mut stored = Settings::load_or_default(&args.config).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    let mut settings = stored.clone();
+    args.apply(&mut settings);
+    if let Err(e) = settings.validate() {
+        eprintln!("{}: {}", args.config.display(), e);
+        std::process::exit(1);
+    }
+
+    // Create logger to output to stdout and a rotating log file.
+    GameLogger::new(&settings.logging).install();
 
     info!("starting up");
 
-    // This allows us to take control of panic!() so we can send it to a file via the logger.
-    panic::set_hook(Box::new(|panic_info| {
-        let bt = Backtrace::new();
-        error!("PANIC: {}, BACKTRACE: {:?}", panic_info, bt);
-    }));
+    // This allows us to take control of panic!() so we can write a crash report along with the log.
+    install_crash_reporter(settings.logging.crash_dir.clone());
+    set_crash_settings(&settings);
 
     // Starts an event gathering type for the window.
     let event_loop = EventLoop::new();
@@ -130,18 +166,19 @@ async fn main() -> Result<(), AscendingError> {
     // Builds the Windows that will be rendered too.
     let window = WindowBuilder::new()
         .with_title("Game")
-        .with_inner_size(PhysicalSize::new(1096, 720))
+        .with_inner_size(LogicalSize::new(settings.graphics.width as f32, settings.graphics.height as f32))
+        .with_min_inner_size(LogicalSize::new(PANEL.size.x, PANEL.size.y))
         .with_visible(false)
-        .with_resizable(false)
+        .with_resizable(true)
         .with_maximized(false)
         .build(&event_loop)
         .unwrap();
 
-    // Generates an Instance for WGPU. Sets WGPU to be allowed on all possible supported backends
+    // Generates an Instance for WGPU. By default WGPU is allowed on all possible supported backends
     // These are DX12, DX11, Vulkan, Metal and Gles. if none of these work on a system they cant
     // play the game basically.
     let instance: wgpu::Instance = wgpu::Instance::new(InstanceDescriptor {
-        backends: Backends::all(),
+        backends: settings.graphics.backend.backends(),
         flags: InstanceFlags::default(),
         dx12_shader_compiler: Dx12Compiler::default(),
         gles_minor_version: wgpu::Gles3MinorVersion::Automatic,
@@ -162,7 +199,7 @@ async fn main() -> Result<(), AscendingError> {
             &wgpu::RequestAdapterOptions {
                 // High performance mode says to use Dedicated Graphics devices first.
                 // Low power is APU graphic devices First.
-                power_preference: wgpu::PowerPreference::HighPerformance,
+                power_preference: settings.graphics.power.preference(),
                 compatible_surface: Some(&compatible_surface),
                 // we will never use this as this forces us to use an alternative renderer.
                 force_fallback_adapter: false,
@@ -175,13 +212,15 @@ async fn main() -> Result<(), AscendingError> {
             },
             None,
             // How we are presenting the screen which causes it to either clip to a FPS limit or be unlimited.
-            wgpu::PresentMode::AutoVsync,
+            settings.graphics.present_mode(),
         )
         .await
         .unwrap();
 
     // we print the GPU it decided to use here for testing purposes.
-    println!("{:?}", renderer.adapter().get_info());
+    let adapter_info = renderer.adapter().get_info();
+    info!("{:?}", adapter_info);
+    set_crash_adapter(&adapter_info);
 
     // We generate Texture atlases to use with out types.
     let mut atlases: Vec<AtlasGroup> = iter::from_fn(|| {
@@ -201,10 +240,18 @@ async fn main() -> Result<(), AscendingError> {
     let text_renderer = TextRenderer::new(&renderer).unwrap();
     let sprite_renderer = ImageRenderer::new(&renderer).unwrap();
     let map_renderer = MapRenderer::new(&mut renderer, 81).unwrap();
+    let mesh_renderer = Mesh2DRenderer::new(&renderer).unwrap();
 
     // get the screen size.
     let mut size = renderer.size();
 
+    // Resolve where everything goes on the screen. The zoom is picked so the
+    // game fits the window, which also covers the monitors scale factor since
+    // the window was sized in logical units.
+    let zoom_mode = settings.graphics.zoom_mode;
+    let mut accessibility = settings.accessibility;
+    let mut layout = ScreenLayout::fit(&size, zoom_mode).with_text_scale(accessibility.text_scale);
+
     // setup our system which includes Camera and projection as well as our controls.
     // for the camera.
     let system = System::new(
@@ -217,82 +264,104 @@ async fn main() -> Result<(), AscendingError> {
             near: 1.0,
             far: -100.0,
         },
-        FlatControls::new(FlatSettings { zoom: SCREEN_ZOOM }),
+        FlatControls::new(FlatSettings { zoom: layout.zoom, ..Default::default() }),
         [size.width, size.height],
     );
 
-    // Create the mouse/keyboard bindings for our stuff.
-    let mut bindings = Bindings::<Action, Axis>::new();
-    bindings.insert_action(
-        Action::Quit,
-        vec![winit::event::VirtualKeyCode::Q.into()],
-    );
+    // Create the mouse/keyboard bindings for our stuff. The menu ones are only read while it is open.
+    let (bindings, menu_bindings) = split_bindings(load_bindings(&settings.paths.bindings));
 
     // set bindings and create our own input handler.
     let mut input_handler = InputHandler::new(bindings);
 
-    // get the Scale factor the pc currently is using for upscaling or downscaling the rendering.
-    let scale = renderer.window().current_monitor().unwrap().scale_factor();
+    // Gamepads are optional so the game still runs when they can not be read.
+    let mut gamepads = open_gamepads();
 
-    // This is how we load a image into a atlas/Texture. It returns the location of the image
-    // within the texture. its x, y, w, h.  Texture loads the file. group_uploads sends it to the Texture
-    // renderer is used to upload it to the GPU when done.
-    let resource = TextureAllocation {
-        ship_texture: Texture::from_file("images/entity/e1.png")?
-            .group_upload(&mut atlases[0], &renderer)
-            .ok_or_else(|| OtherError::new("failed to upload image"))?,
-        icon_texture: Texture::from_file("images/entity/e2.png")?
-            .group_upload(&mut atlases[0], &renderer)
-            .ok_or_else(|| OtherError::new("failed to upload image"))?,
-        explosion_texture: Texture::from_file("images/animation/a2.png")?
-            .group_upload(&mut atlases[0], &renderer)
-            .ok_or_else(|| OtherError::new("failed to upload image"))?,
-        game_bg_texture: Texture::from_file("images/gui/game_bg.png")?
-            .group_upload(&mut atlases[0], &renderer)
-            .ok_or_else(|| OtherError::new("failed to upload image"))?,
-        result_texture: Texture::from_file("images/gui/result.png")?
-            .group_upload(&mut atlases[0], &renderer)
-            .ok_or_else(|| OtherError::new("failed to upload image"))?,
-        white_texture: Texture::from_file("images/white.png")?
-            .group_upload(&mut atlases[0], &renderer)
-            .ok_or_else(|| OtherError::new("failed to upload image"))?,
+    // Input can be recorded to a file for playing back later, such as to reproduce a bug.
+    let mut recorder = args.record.as_ref().map(|_| InputRecorder::new(1.0));
+    let mut playback = match &args.replay {
+        Some(path) => Some(InputPlayback::new(InputRecording::load(path).map_err(|e| {
+            OtherError::new(&format!("failed to load input recording {}: {}", path.display(), e))
+        })?)),
+        None => None,
     };
-    let _tilesheet = Texture::from_file("images/tiles/1.png")?
-        .new_tilesheet(&mut atlases[1], &renderer, 20)
-        .ok_or_else(|| OtherError::new("failed to upload tiles"))?;
+
+    // Themes hold the paths of every image we use along with colors and fonts.
+    // The one named in the settings is used, or the first one found if it is missing.
+    let themes = Theme::load_all(&settings.paths.themes)?;
+    if themes.is_empty() {
+        return Err(OtherError::new(&format!("no themes found in {}", settings.paths.themes.display())).into());
+    }
+    let mut theme_index = find_or_first(&themes, "theme", &settings.gameplay.theme, |theme| &theme.name);
+
+    // Locales hold every string shown in game. English sorts first so it is the default.
+    let locales = Locale::load_all(&settings.paths.locales)?;
+    if locales.is_empty() {
+        return Err(OtherError::new(&format!("no locales found in {}", settings.paths.locales.display())).into());
+    }
+    let mut locale_index = find_or_first(&locales, "language", &settings.gameplay.language, |locale| &locale.code);
+
+    // This is how we load a image into a atlas/Texture. It returns the location of the image
+    // within the texture. its x, y, w, h. The theme loads the files and uploads them into
+    // the atlases, the tiles get their own atlas as a tilesheet.
+    let mut image_atlas = atlases.remove(0);
+    let mut map_atlas = atlases.remove(0);
+    let mut resource = themes[theme_index].upload(&mut image_atlas, &mut map_atlas, &mut renderer)?;
+    locales[locale_index].load_fonts(&mut renderer);
+
+    // Ships, markers, effects and the GUI panel are entities in here.
+    let mut world = World::new();
 
     // Create Board data
-    let mut gameboard = GameBoard::new(&resource, &mut renderer, &scale);
+    let mut gameboard = GameBoard::new(&resource, &mut renderer, &layout, &themes[theme_index], &locales[locale_index]);
     let mut boards = [
-        Board::new(&mut renderer, Vec2::new(27.0, 11.0)),
-        Board::new(&mut renderer, Vec2::new(297.0, 11.0)),
+        Board::new(&mut renderer, 0, layout.boards[0].pos),
+        Board::new(&mut renderer, 1, layout.boards[1].pos),
     ];
-    for i in 0..=1 {
-        boards[i].prepare_board(&resource, &mut renderer, &mut gameboard, i);
+    for board in boards.iter_mut() {
+        board.prepare_board(&mut world, &resource, &mut renderer, &mut gameboard);
+        board.set_accessibility(&mut world, &accessibility);
     }
 
-    // Setup Manual Animation
-    let mut animation = Animation::new(&resource, &mut renderer);
+    // Explosions, splashes and sinking ships play as effects queued up here.
+    let mut effects = Effects::new();
+    effects.reduced_motion = accessibility.reduced_motion;
+
+    // No sounds are bundled yet so everything goes through the silent backend.
+    let cues = CueMap::load(&settings.paths.sound_cues).unwrap_or_else(|e| {
+        warn!("{}", e);
+        CueMap::default()
+    });
+    let mut audio = Audio::new(NullBackend, cues, layout.panel);
+    audio.mixer = settings.audio;
+    audio.set_scene(Scene::Battle);
+
+    // Every shot taken so far, kept so the match can be followed from the start.
+    let mut history = MatchHistory::default();
+    set_crash_snapshot(&MatchSnapshot::new(&gameboard, &boards, &history));
+
+    // High contrast outlines over the boards.
+    let mut outline = BoardOutline::new(&mut renderer);
 
     // GUI
-    let mut guis = Vec::with_capacity(1);
-    let mut gui = Image::new(Some(resource.game_bg_texture), &mut renderer, 1);
-    gui.pos = Vec3::new(0.0, 0.0, GUI_BG_ORDER);
-    gui.hw = Vec2::new(548.0, 360.0);
-    gui.uv = Vec4::new(0.0, 0.0, 548.0, 360.0);
-    gui.color = Color::rgba(255, 255, 255, 255);
-    guis.push(gui);
+    let gui_panel = world.spawn((
+        Transform::new(Vec3::new(layout.panel.pos.x, layout.panel.pos.y, GUI_BG_ORDER), layout.panel.size),
+        Sprite::new(SpriteTexture::GameBg, &resource, &mut renderer, Vec4::new(0.0, 0.0, 548.0, 360.0)),
+        GuiPanel,
+    ));
 
     // create a Text rendering object.
     let mut text = Text::new(
         &mut renderer,
-        Some(Metrics::new(16.0, 16.0).scale(scale as f32)),
-        Vec3::new(5.0, 5.0, 0.0),
+        Some(layout.font_metrics()),
+        Vec3::new(0.0, 0.0, 0.0),
         Vec2::new(100.0, 16.0),
     );
-    text.set_buffer_size(&mut renderer, size.width as i32, size.height as i32)
-        .set_bounds(Some(Bounds::new(0.0, 0.0, 100.0, 21.0)))
-        .set_default_color(Color::rgba(255, 255, 255, 255));
+    text.set_default_color(Palette::color(themes[theme_index].palette.debug_text));
+    layout.place_text(&mut text, &layout.fps_text, &mut renderer);
+
+    let mut options = OptionsMenu::new(&resource, &mut renderer, &layout, &themes[theme_index], &locales[locale_index], &accessibility);
+    let mut chat = ChatPanel::new(&resource, &mut renderer, &layout, &themes[theme_index]);
 
     // Allow the window to be seen. hiding it then making visible speeds up
     // load times.
@@ -301,21 +370,23 @@ async fn main() -> Result<(), AscendingError> {
     // add everything into our convience type for quicker access and passing.
     let mut state = State {
         system,
-        guis,
-        image_atlas: atlases.remove(0),
+        image_atlas,
         sprite_renderer,
         text_atlas,
         text_renderer,
         map_renderer,
-        map_atlas: atlases.remove(0),
+        mesh_renderer,
+        map_atlas,
+        clear_color: themes[theme_index].palette.clear_color(),
     };
 
     let mut frame_time = FrameTime::new();
-    let mut time = 0.0f32;
-    let mut fps = 0u32;
+    frame_time.set_max_fps(settings.graphics.max_fps());
+    // Refreshes the frame stats shown each second.
+    let mut fps_timer = Timer::repeating(0.0, 1.0);
 
-    // Buttons
-    let mut did_key_press = [false; ACTION_SIZE];
+    // Set when something other than the window size needs everything laid out again.
+    let mut relayout = false;
 
     #[allow(deprecated)]
     event_loop.run(move |event, _, control_flow| {
@@ -333,6 +404,14 @@ async fn main() -> Result<(), AscendingError> {
             Event::MainEventsCleared => {
                 renderer.window().request_redraw();
             }
+            Event::LoopDestroyed => {
+                if let (Some(recorder), Some(path)) = (&recorder, &args.record) {
+                    match recorder.recording().save(path) {
+                        Ok(()) => info!("input recorded to {}", path.display()),
+                        Err(e) => error!("failed to save input recording {}: {}", path.display(), e),
+                    }
+                }
+            }
             _ => {}
         }
 
@@ -349,14 +428,36 @@ async fn main() -> Result<(), AscendingError> {
             return;
         }
 
+        // A replay stands in for the player, so live input is left out until it finishes.
+        // Closing and resizing still go through above and in the renderer.
+        let replaying = playback.as_ref().is_some_and(|playback| !playback.is_finished());
+
         // update our inputs.
-        input_handler.update(renderer.window(), &event, 1.0);
+        if !replaying {
+            input_handler.update(renderer.window(), &event, 1.0);
+            if let Some(recorder) = &mut recorder {
+                recorder.record_winit(renderer.window(), &event, &frame_time);
+            }
+        }
 
         // update our renderer based on events here
         if !renderer.update(&event).unwrap() {
             return;
         }
 
+        if let Some(gamepads) = gamepads.as_deref_mut().filter(|_| !replaying) {
+            for event in input_handler.poll_gamepads(gamepads) {
+                if let Some(recorder) = &mut recorder {
+                    recorder.record(InputEvent::Gamepad(event), &frame_time);
+                }
+            }
+        }
+
+        // feed in the recorded input for this frame.
+        if let Some(playback) = &mut playback {
+            playback.play_frame(&mut input_handler, frame_time.frame());
+        }
+
         if size != new_size {
             size = new_size;
 
@@ -371,48 +472,211 @@ async fn main() -> Result<(), AscendingError> {
             });
 
             renderer.update_depth_texture();
+            relayout = true;
+        }
+
+        if relayout {
+            relayout = false;
+
+            // Refit the zoom and move everything to where the layout says it belongs now.
+            layout = ScreenLayout::fit(&new_size, zoom_mode).with_text_scale(accessibility.text_scale);
+            state.system.controls_mut().set_zoom(layout.zoom);
+            gameboard.set_layout(&layout, &mut renderer);
+            for (board, rect) in boards.iter_mut().zip(layout.boards.iter()) {
+                board.set_pos(&mut world, rect.pos);
+            }
+            if let Ok(transform) = world.query_one_mut::<&mut Transform>(gui_panel) {
+                transform.pos = Vec3::new(layout.panel.pos.x, layout.panel.pos.y, GUI_BG_ORDER);
+            }
+            layout.place_text(&mut text, &layout.fps_text, &mut renderer);
+            options.set_layout(&layout, &mut renderer);
+            chat.set_layout(&layout, &mut renderer);
+            outline.changed = true;
+            audio.set_listener(layout.panel);
         }
 
         let seconds = frame_time.seconds();
+        // Board effects run on the scaled time so they stop while paused.
+        let game_seconds = frame_time.scaled_seconds();
+
+        let open_chat = input_handler.is_action_pressed(&Action::Chat);
+        let options_pressed = input_handler.is_action_pressed(&Action::Options);
+        // Holding a direction keeps stepping through the menu.
+        let stepped = |action: &Action| input_handler.is_action_pressed(action) || input_handler.is_action_repeated(action);
+        let up = stepped(&Action::Up);
+        let down = stepped(&Action::Down);
+        let prev = stepped(&Action::Prev);
+        let next = stepped(&Action::Next);
+
+        // While typing every key goes to the chat instead of the actions.
+        if chat.focused {
+            chat.handle_input(&input_handler, seconds, &mut renderer);
+            if chat.focused {
+                let (x, y) = chat.ime_position(new_size.height);
+                renderer.window().set_ime_position(PhysicalPosition::new(x, y));
+            } else {
+                input_handler.remove_context(CHAT_CONTEXT);
+                renderer.window().set_ime_allowed(false);
+            }
+        } else if open_chat {
+            chat.open();
+            input_handler.push_context(InputContext::new(CHAT_CONTEXT, Bindings::new()).with_text_entry());
+            // Lets languages the keyboard can't type directly be composed.
+            renderer.window().set_ime_allowed(true);
+        }
+
+        // There is no match connection yet to send these over, so they are only shown here.
+        for text in chat.take_outgoing() {
+            let player = gameboard.current_turn + 1;
+            let message = locales[locale_index].text("chat-message", &[("player", &player), ("text", &text)]);
+            chat.push_message(message, seconds, &mut renderer);
+        }
 
         // check if out close action was hit for esc
         if input_handler.is_action_down(&Action::Quit) {
             *control_flow = ControlFlow::Exit;
         }
+
+        // The menu keeps every button from the board below it while open and pauses the board effects.
+        if options_pressed {
+            options.toggle();
+            frame_time.set_paused(options.visible);
+            if options.visible {
+                input_handler.push_context(InputContext::new(MENU_CONTEXT, menu_bindings.clone()).with_consume(Consume::All));
+            } else {
+                input_handler.remove_context(MENU_CONTEXT);
+            }
+        }
+
+        // Up and Down pick the row, Prev and Next change what is in it.
+        if up != down {
+            let step = if up { -1 } else { 1 };
+            options.select_row(step, &themes[theme_index], &locales[locale_index], &accessibility, &mut renderer);
+        }
+
+        let step = match (prev, next) {
+            (true, false) => -1,
+            (false, true) => 1,
+            _ => 0,
+        };
+        if step != 0 {
+            match options.selected {
+                // Swap the theme by reuploading everything into the atlases.
+                OptionsRow::Theme if themes.len() > 1 => {
+                    let next_index = cycle_index(theme_index, step, themes.len());
+                    let theme = &themes[next_index];
+                    let locale = &locales[locale_index];
+
+                    match theme.upload(&mut state.image_atlas, &mut state.map_atlas, &mut renderer) {
+                        Ok(new_resource) => {
+                            theme_index = next_index;
+                            resource = new_resource;
+
+                            gameboard.set_theme(theme, &resource, locale, &mut renderer);
+                            set_sprite_theme(&mut world, &resource);
+                            options.set_theme(theme, locale, &accessibility, &resource, &mut renderer);
+                            chat.set_theme(theme, &resource, &mut renderer);
+                            state.clear_color = theme.palette.clear_color();
+                            text.set_default_color(Palette::color(theme.palette.debug_text));
+                            fps_timer = Timer::repeating(seconds, 1.0);
+                        }
+                        Err(e) => error!("{}", e),
+                    }
+                }
+                OptionsRow::Language if locales.len() > 1 => {
+                    locale_index = cycle_index(locale_index, step, locales.len());
+                    let locale = &locales[locale_index];
+
+                    locale.load_fonts(&mut renderer);
+                    gameboard.set_locale(locale, &mut renderer);
+                    options.set_text(&themes[theme_index], locale, &accessibility, &mut renderer);
+                    fps_timer = Timer::repeating(seconds, 1.0);
+                }
+                OptionsRow::Colors => {
+                    let index = ColorMode::ALL.iter().position(|mode| *mode == accessibility.color_mode).unwrap_or(0);
+                    accessibility.color_mode = ColorMode::ALL[cycle_index(index, step, ColorMode::ALL.len())];
+
+                    boards.iter_mut().for_each(|board| board.set_accessibility(&mut world, &accessibility));
+                    outline.changed = true;
+                }
+                OptionsRow::HighContrast => {
+                    accessibility.high_contrast = !accessibility.high_contrast;
+                    outline.changed = true;
+                }
+                OptionsRow::TextSize => {
+                    let index = TEXT_SCALES.iter().position(|scale| *scale == accessibility.text_scale).unwrap_or(0);
+                    accessibility.text_scale = TEXT_SCALES[cycle_index(index, step, TEXT_SCALES.len())];
+                    relayout = true;
+                }
+                OptionsRow::ReducedMotion => {
+                    accessibility.reduced_motion = !accessibility.reduced_motion;
+                    effects.reduced_motion = accessibility.reduced_motion;
+                }
+                _ => {}
+            }
+
+            options.set_text(&themes[theme_index], &locales[locale_index], &accessibility, &mut renderer);
+
+            // Keep what was picked for the next time the game starts.
+            for settings in [&mut settings, &mut stored] {
+                settings.gameplay.theme = themes[theme_index].name.clone();
+                settings.gameplay.language = locales[locale_index].code.clone();
+                settings.accessibility = accessibility;
+            }
+            if let Err(e) = stored.save(&args.config) {
+                warn!("{}", e);
+            }
+            set_crash_settings(&settings);
+        }
+
         // Check input
-        if input_handler.is_mouse_button_down(MouseButton::Left) {
-            if !did_key_press[action_index(Action::Select)] {
-                did_key_press[action_index(Action::Select)] = true;
-                
-                let mouse_pos = input_handler.mouse_position().unwrap();
-                let tile_pos = in_map_pos(&Vec2::new(mouse_pos.0, mouse_pos.1), &boards, &size);
-                
-                if !tile_pos.is_none() && !gameboard.got_winner {
-                    let board_data = find_map_by_pos(&Vec2::new(mouse_pos.0, mouse_pos.1), &boards, &size);
-                    if !board_data.is_none() {
-                        let board_index = board_data.unwrap() as usize;
-                        if gameboard.current_turn != board_index as i32 {
-                            let hit_result = boards[board_index].hit_place(&tile_pos.unwrap(), &resource, &mut renderer, &mut animation, &mut gameboard);
-                            if !hit_result.is_none() {
-                                let got_winner = hit_result.unwrap();
-                                if got_winner {
-                                    gameboard.set_winner(gameboard.current_turn);
-                                    gameboard.change_status_text("", &mut renderer);
-                                } else {
-                                    gameboard.current_turn = board_index as i32;
-                                    gameboard.change_status_text(&format!("PLAYER {} TURN", gameboard.current_turn + 1), &mut renderer);
-                                }
+        // Select can be rebound to a key, which may be pressed before the mouse ever moved.
+        if let Some(mouse_pos) = input_handler.mouse_position().filter(|_| input_handler.is_action_pressed(&Action::Select)) {
+            let tile_pos = in_map_pos(&Vec2::new(mouse_pos.0, mouse_pos.1), &boards, &size, layout.zoom);
+            
+            if !tile_pos.is_none() && !gameboard.got_winner {
+                let board_data = find_map_by_pos(&Vec2::new(mouse_pos.0, mouse_pos.1), &boards, &size, layout.zoom);
+                if !board_data.is_none() {
+                    let board_index = board_data.unwrap() as usize;
+                    if gameboard.current_turn != board_index as i32 {
+                        let hit_result = boards[board_index].hit_place(&mut world, &tile_pos.unwrap(), &resource, &mut renderer, &mut effects, game_seconds, &mut gameboard, &mut audio);
+                        if !hit_result.is_none() {
+                            outline.changed = true;
+                            let got_winner = hit_result.unwrap();
+                            history.record(board_index, &boards[board_index].data, tile_pos.unwrap(), got_winner);
+                            if got_winner {
+                                gameboard.set_winner(gameboard.current_turn, &layout);
+                                audio.emit(AudioEvent::new(AudioEventKind::Victory));
+                                audio.set_scene(Scene::Victory);
+                                let status = Status::Winner {
+                                    player: gameboard.current_turn + 1,
+                                    ships_left: gameboard.ships_left(gameboard.current_turn as usize),
+                                };
+                                gameboard.set_status(status, &locales[locale_index], &mut renderer);
+                            } else {
+                                gameboard.current_turn = board_index as i32;
+                                gameboard.set_status(Status::Turn(gameboard.current_turn + 1), &locales[locale_index], &mut renderer);
                             }
+                            set_crash_snapshot(&MatchSnapshot::new(&gameboard, &boards, &history));
                         }
                     }
                 }
             }
-        } else {
-            did_key_press[action_index(Action::Select)] = false;
         }
 
-        // Handle Manual Animation
-        animation.update_frame(seconds);
+        // Handle Effects and other animations
+        effects.update(&mut world, &boards, &resource, &mut renderer, game_seconds);
+        update_animations(&mut world, game_seconds);
+        update_fades(&mut world, game_seconds);
+        remove_finished_effects(&mut world);
+        audio.update(frame_time.delta_seconds());
+
+        if accessibility.high_contrast && outline.changed {
+            let target = if gameboard.got_winner { None } else { Some(1 - gameboard.current_turn as usize) };
+            if let Err(e) = outline.rebuild(&boards, target, &accessibility.markers()) {
+                error!("{}", e);
+            }
+        }
 
         // update our systems data to the gpu. this is the Camera in the shaders.
         state.system.update(&renderer, &frame_time);
@@ -420,10 +684,9 @@ async fn main() -> Result<(), AscendingError> {
         state.system.update_screen(&renderer, [new_size.width, new_size.height]);
 
         // This adds the Image data to the Buffer for rendering.
+        // Ships, markers, effects and the GUI panel.
+        submit_sprites(&mut world, &mut state.sprite_renderer, &mut renderer);
         // GUI
-        state.guis.iter_mut().for_each(|gui| {
-            state.sprite_renderer.image_update(gui, &mut renderer);
-        });
         if gameboard.got_winner {
             state.sprite_renderer.image_update(&mut gameboard.board_shade[0], &mut renderer);
             state.sprite_renderer.image_update(&mut gameboard.board_shade[1], &mut renderer);
@@ -432,20 +695,28 @@ async fn main() -> Result<(), AscendingError> {
         } else {
             state.sprite_renderer.image_update(&mut gameboard.board_shade[gameboard.current_turn as usize], &mut renderer);
         }
-        // Animation
-        if animation.in_play { state.sprite_renderer.image_update(&mut animation.sprite, &mut renderer); }
+        // Options
+        if options.visible {
+            state.sprite_renderer.image_update(&mut options.background, &mut renderer);
+        }
+        // Chat
+        let chat_visible = chat.is_visible(seconds);
+        if chat_visible {
+            state.sprite_renderer.image_update(&mut chat.background, &mut renderer);
+        }
+        if chat.focused {
+            if chat.edit.selection().is_some() && chat.composition.is_empty() {
+                state.sprite_renderer.image_update(&mut chat.selection, &mut renderer);
+            }
+            state.sprite_renderer.image_update(&mut chat.caret, &mut renderer);
+        }
         // Board
         boards.iter_mut().for_each(|board| {
-            board.ship.iter_mut().for_each(|ship| {
-                if ship.visible {
-                    state.sprite_renderer.image_update(&mut ship.sprite, &mut renderer);
-                }
-            });
-            board.icon.iter_mut().for_each(|icon| {
-                state.sprite_renderer.image_update(icon, &mut renderer);
-            });
             state.map_renderer.map_update(&mut board.map, &mut renderer);
         });
+        if accessibility.high_contrast && !outline.is_empty() {
+            state.mesh_renderer.mesh_update(&mut outline.mesh, &mut renderer);
+        }
         // Text
         state.text_renderer.text_update(&mut text, &mut state.text_atlas, &mut renderer).unwrap();
         state.text_renderer.text_update(&mut gameboard.status_text, &mut state.text_atlas, &mut renderer).unwrap();
@@ -454,12 +725,25 @@ async fn main() -> Result<(), AscendingError> {
                 state.text_renderer.text_update(&mut gameboard.ship_counter[x][y], &mut state.text_atlas, &mut renderer).unwrap();
             }
         }
+        if options.visible {
+            state.text_renderer.text_update(&mut options.title, &mut state.text_atlas, &mut renderer).unwrap();
+            for text in options.rows.iter_mut() {
+                state.text_renderer.text_update(text, &mut state.text_atlas, &mut renderer).unwrap();
+            }
+        }
+        if chat_visible {
+            state.text_renderer.text_update(&mut chat.log, &mut state.text_atlas, &mut renderer).unwrap();
+        }
+        if chat.focused {
+            state.text_renderer.text_update(&mut chat.input, &mut state.text_atlas, &mut renderer).unwrap();
+        }
         // this cycles all the Image's in the Image buffer by first putting them in rendering order
         // and then uploading them to the GPU if they have moved or changed in any way. clears the
         // Image buffer for the next render pass. Image buffer only holds the ID's and Sortign info
         // of the finalized Indicies of each Image.
         state.sprite_renderer.finalize(&mut renderer);
         state.map_renderer.finalize(&mut renderer);
+        state.mesh_renderer.finalize(&mut renderer);
         state.text_renderer.finalize(&mut renderer);
 
         // Start encoding commands. this stores all the rendering calls for execution when
@@ -476,20 +760,26 @@ async fn main() -> Result<(), AscendingError> {
         // Also tells the system to begin running the commands on the GPU.
         renderer.queue().submit(std::iter::once(encoder.finish()));
 
-        if time < seconds {
+        if fps_timer.tick(seconds) {
+            let stats = frame_time.stats();
+            let fps = stats.fps().round();
+            let low = stats.low_fps().round();
+            let max = format!("{:.1}", stats.max() * 1000.0);
             text.set_text(
                 &mut renderer,
-                &format!("FPS: {fps}"),
-                Attrs::new(),
+                &locales[locale_index].text("fps", &[("fps", &fps), ("low", &low), ("max", &max)]),
+                themes[theme_index].attrs(),
             );
-            fps = 0u32;
-            time = seconds + 1.0;
         }
 
-        fps += 1;
-
         input_handler.end_frame();
+        // Holds to the max fps setting on top of whatever vsync does.
+        frame_time.pace();
         frame_time.update();
+        // Held keys, double clicks and touches are timed from this. A replay keeps the recorded time instead.
+        if !replaying {
+            input_handler.set_time(frame_time.seconds());
+        }
         renderer.present().unwrap();
 
         // These clear the Last used image tags.
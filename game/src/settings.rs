@@ -0,0 +1,425 @@
+mod cli;
+
+pub use cli::*;
+
+use graphics::*;
+use input::{BindingProblem, Bindings, GamepadButton};
+use log::{warn, LevelFilter};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+use winit::event::{MouseButton, VirtualKeyCode};
+
+use crate::accessibility::{Accessibility, TEXT_SCALES};
+use crate::audio::Mixer;
+use crate::func::{load_toml, save_toml};
+use crate::layout::{ZoomMode, PANEL};
+use crate::{Action, Axis, SCREEN_ZOOM};
+
+/// Everything the game reads at startup. Missing fields fall back to their
+/// defaults so older files keep loading.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub graphics: GraphicsSettings,
+    pub audio: Mixer,
+    pub gameplay: GameplaySettings,
+    pub accessibility: Accessibility,
+    pub paths: PathSettings,
+    pub logging: LogSettings,
+}
+
+impl Settings {
+    /// Loads the file or returns the defaults if there is none. The
+    /// defaults are written out so there is a file to edit.
+    pub fn load_or_default(
+        path: impl AsRef<Path>,
+    ) -> Result<Self, AscendingError> {
+        let path = path.as_ref();
+
+        if path.exists() {
+            return load_toml(path);
+        }
+
+        // Settings pick how logging is set up so nothing logs yet.
+        let settings = Self::default();
+        if let Err(e) = settings.save(path) {
+            eprintln!("{}", e);
+        }
+
+        Ok(settings)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), AscendingError> {
+        save_toml(path, self)
+    }
+
+    /// Checks every value, reporting all problems at once so they can be
+    /// fixed in one go.
+    pub fn validate(&self) -> Result<(), AscendingError> {
+        let mut problems = Vec::new();
+        let graphics = &self.graphics;
+
+        if (graphics.width as f32) < PANEL.size.x
+            || (graphics.height as f32) < PANEL.size.y
+        {
+            problems.push(format!(
+                "graphics.width and graphics.height must be at least {}x{}, got {}x{}",
+                PANEL.size.x, PANEL.size.y, graphics.width, graphics.height
+            ));
+        }
+
+        for (name, volume) in [
+            ("master", self.audio.master),
+            ("effects", self.audio.effects),
+            ("music", self.audio.music),
+            ("interface", self.audio.interface),
+        ] {
+            if !(0.0..=1.0).contains(&volume) {
+                problems.push(format!(
+                    "audio.{} must be from 0.0 to 1.0, got {}",
+                    name, volume
+                ));
+            }
+        }
+
+        if !TEXT_SCALES.contains(&self.accessibility.text_scale) {
+            problems.push(format!(
+                "accessibility.text_scale must be one of {:?}, got {}",
+                TEXT_SCALES, self.accessibility.text_scale
+            ));
+        }
+
+        for (name, dir) in [
+            ("themes", &self.paths.themes),
+            ("locales", &self.paths.locales),
+        ] {
+            if !dir.is_dir() {
+                problems.push(format!(
+                    "paths.{} is not a folder: {}",
+                    name,
+                    dir.display()
+                ));
+            }
+        }
+
+        if let Err(e) = self.logging.level_filter() {
+            problems.push(e.to_string());
+        }
+        for (module, level) in &self.logging.modules {
+            if let Err(e) =
+                parse_level(&format!("logging.modules.\"{}\"", module), level)
+            {
+                problems.push(e.to_string());
+            }
+        }
+        if self.logging.max_file_kb == 0 {
+            problems.push("logging.max_file_kb must be more than 0".to_owned());
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(AscendingError::Other(OtherError::new(&format!(
+                "invalid settings:\n  {}",
+                problems.join("\n  ")
+            ))))
+        }
+    }
+}
+
+/// Which GPUs wgpu should prefer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PowerMode {
+    /// Dedicated graphics first.
+    HighPerformance,
+    /// Integrated graphics first.
+    LowPower,
+}
+
+impl PowerMode {
+    pub fn preference(&self) -> wgpu::PowerPreference {
+        match self {
+            PowerMode::HighPerformance => {
+                wgpu::PowerPreference::HighPerformance
+            }
+            PowerMode::LowPower => wgpu::PowerPreference::LowPower,
+        }
+    }
+}
+
+/// Graphics APIs wgpu is allowed to pick from.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackendMode {
+    All,
+    Vulkan,
+    Metal,
+    Dx12,
+    Gl,
+}
+
+impl BackendMode {
+    pub fn backends(&self) -> wgpu::Backends {
+        match self {
+            BackendMode::All => wgpu::Backends::all(),
+            BackendMode::Vulkan => wgpu::Backends::VULKAN,
+            BackendMode::Metal => wgpu::Backends::METAL,
+            BackendMode::Dx12 => wgpu::Backends::DX12,
+            BackendMode::Gl => wgpu::Backends::GL,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GraphicsSettings {
+    /// Window size in logical pixels when it first opens.
+    pub width: u32,
+    pub height: u32,
+    /// Limits the frame rate to the monitors refresh rate.
+    pub vsync: bool,
+    /// Frames a second the game holds to, with or without vsync. 0 for no
+    /// limit.
+    pub max_fps: u32,
+    pub power: PowerMode,
+    pub backend: BackendMode,
+    pub zoom_mode: ZoomMode,
+}
+
+impl Default for GraphicsSettings {
+    fn default() -> Self {
+        Self {
+            width: (PANEL.size.x * SCREEN_ZOOM) as u32,
+            height: (PANEL.size.y * SCREEN_ZOOM) as u32,
+            vsync: true,
+            max_fps: 0,
+            power: PowerMode::HighPerformance,
+            backend: BackendMode::All,
+            zoom_mode: ZoomMode::Integer,
+        }
+    }
+}
+
+impl GraphicsSettings {
+    pub fn max_fps(&self) -> Option<f32> {
+        (self.max_fps > 0).then_some(self.max_fps as f32)
+    }
+
+    pub fn present_mode(&self) -> wgpu::PresentMode {
+        if self.vsync {
+            wgpu::PresentMode::AutoVsync
+        } else {
+            wgpu::PresentMode::AutoNoVsync
+        }
+    }
+}
+
+/// Actions only read while the options menu is open.
+const MENU_ACTIONS: [Action; 4] =
+    [Action::Up, Action::Down, Action::Prev, Action::Next];
+
+/// Keys each Action starts out bound to.
+pub fn default_bindings() -> Bindings<Action, Axis> {
+    let mut bindings = Bindings::new();
+
+    for (action, key) in [
+        (Action::Quit, VirtualKeyCode::Q),
+        (Action::Options, VirtualKeyCode::O),
+        (Action::Prev, VirtualKeyCode::Left),
+        (Action::Next, VirtualKeyCode::Right),
+        (Action::Up, VirtualKeyCode::Up),
+        (Action::Down, VirtualKeyCode::Down),
+        (Action::Chat, VirtualKeyCode::Return),
+    ] {
+        bindings.insert_action(action, vec![key.into()]);
+    }
+    bindings.insert_action(Action::Select, vec![MouseButton::Left.into()]);
+
+    for (action, button) in [
+        (Action::Options, GamepadButton::Start),
+        (Action::Prev, GamepadButton::DPadLeft),
+        (Action::Next, GamepadButton::DPadRight),
+        (Action::Up, GamepadButton::DPadUp),
+        (Action::Down, GamepadButton::DPadDown),
+    ] {
+        bindings.insert_action(action, vec![button.into()]);
+    }
+
+    bindings
+}
+
+/// Splits the loaded bindings into the gameplay ones and the ones of the
+/// options menu context. The menu can still be closed and the game quit
+/// while it is open.
+pub fn split_bindings(
+    bindings: Bindings<Action, Axis>,
+) -> (Bindings<Action, Axis>, Bindings<Action, Axis>) {
+    let mut gameplay = bindings;
+    let mut menu = Bindings::new();
+
+    for action in [Action::Options, Action::Quit] {
+        menu.set_action(action.clone(), gameplay.action(&action).to_vec());
+    }
+    for action in MENU_ACTIONS {
+        menu.set_action(action.clone(), gameplay.action(&action).to_vec());
+        gameplay.remove_action(&action);
+    }
+
+    (gameplay, menu)
+}
+
+/// Loads the bindings file on top of the defaults, logging anything wrong
+/// with it. The defaults are written out if there is no file yet.
+pub fn load_bindings(path: &Path) -> Bindings<Action, Axis> {
+    let defaults = default_bindings();
+
+    if !path.exists() {
+        if let Err(e) = defaults.save(path) {
+            warn!("failed to save bindings to {}: {}", path.display(), e);
+        }
+        return defaults;
+    }
+
+    match Bindings::load(path, &defaults) {
+        Ok((bindings, problems)) => {
+            for problem in problems {
+                match problem {
+                    BindingProblem::Conflict { .. } => {
+                        warn!("{}: {}", path.display(), problem)
+                    }
+                    _ => warn!("{}: {}, skipped", path.display(), problem),
+                }
+            }
+            bindings
+        }
+        Err(e) => {
+            warn!(
+                "failed to load bindings from {}, using the defaults: {}",
+                path.display(),
+                e
+            );
+            defaults
+        }
+    }
+}
+
+/// What the game starts with. Updated when it is changed in the options
+/// menu.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameplaySettings {
+    /// Theme name. The first theme is used when empty or not found.
+    pub theme: String,
+    /// Locale code such as en. The first locale is used when empty or not
+    /// found.
+    pub language: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PathSettings {
+    pub themes: PathBuf,
+    pub locales: PathBuf,
+    pub sound_cues: PathBuf,
+    /// Keys bound to each action.
+    pub bindings: PathBuf,
+}
+
+impl Default for PathSettings {
+    fn default() -> Self {
+        Self {
+            themes: PathBuf::from("themes"),
+            locales: PathBuf::from("locales"),
+            sound_cues: PathBuf::from("sounds/cues.toml"),
+            bindings: PathBuf::from("bindings.toml"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogSettings {
+    /// off, error, warn, info, debug or trace.
+    pub level: String,
+    /// Levels for single modules such as "game::audio" = "debug". The
+    /// longest module that matches wins.
+    pub modules: BTreeMap<String, String>,
+    pub file: PathBuf,
+    /// Size the log file can grow to before it is rotated.
+    pub max_file_kb: u64,
+    /// Rotated log files kept as file.1, file.2 and so on.
+    pub max_files: usize,
+    /// Folder crash reports are written into.
+    pub crash_dir: PathBuf,
+}
+
+impl Default for LogSettings {
+    fn default() -> Self {
+        Self {
+            level: "info".to_owned(),
+            modules: BTreeMap::new(),
+            file: PathBuf::from("logs/game.log"),
+            max_file_kb: 1024,
+            max_files: 5,
+            crash_dir: PathBuf::from("crashes"),
+        }
+    }
+}
+
+impl LogSettings {
+    pub fn level_filter(&self) -> Result<LevelFilter, AscendingError> {
+        parse_level("logging.level", &self.level)
+    }
+
+    pub fn module_filters(
+        &self,
+    ) -> Result<Vec<(String, LevelFilter)>, AscendingError> {
+        self.modules
+            .iter()
+            .map(|(module, level)| {
+                Ok((module.clone(), parse_level(module, level)?))
+            })
+            .collect()
+    }
+}
+
+fn parse_level(name: &str, level: &str) -> Result<LevelFilter, AscendingError> {
+    level.parse().map_err(|_| {
+        AscendingError::Other(OtherError::new(&format!(
+            "{} must be off, error, warn, info, debug or trace, got {}",
+            name, level
+        )))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_reports_every_problem_at_once() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+        let mut settings = Settings::default();
+        settings.paths.themes = root.join("themes");
+        settings.paths.locales = root.join("locales");
+        assert!(settings.validate().is_ok());
+
+        settings.graphics.width = 10;
+        settings.audio.music = 1.5;
+        settings.logging.level = "loud".to_owned();
+        let message = settings.validate().unwrap_err().to_string();
+
+        for problem in [
+            "graphics.width and graphics.height must be at least",
+            "audio.music must be from 0.0 to 1.0, got 1.5",
+            "logging.level must be off",
+        ] {
+            assert!(message.contains(problem), "{}", message);
+        }
+        assert!(!message.contains("paths."), "{}", message);
+    }
+}
@@ -0,0 +1,264 @@
+use graphics::*;
+use serde::de::DeserializeOwned;
+use std::path::PathBuf;
+
+use super::{BackendMode, PowerMode, Settings};
+use crate::layout::ZoomMode;
+
+pub const USAGE: &str = "\
+Usage: game [OPTIONS]
+
+Options:
+  --config <FILE>      Settings file to load and save [default: settings.toml]
+  --width <PIXELS>     Window width
+  --height <PIXELS>    Window height
+  --vsync <on|off>     Limit the frame rate to the monitors refresh rate
+  --max-fps <FPS>      Limit the frame rate, 0 for no limit
+  --power <MODE>       high_performance or low_power
+  --backend <BACKEND>  all, vulkan, metal, dx12 or gl
+  --zoom <MODE>        integer or fractional
+  --log-level <LEVEL>  off, error, warn, info, debug or trace
+  --themes <DIR>       Folder to load themes from
+  --locales <DIR>      Folder to load locales from
+  --sound-cues <FILE>  Sound cue file to load
+  --bindings <FILE>    Key bindings file to load and save
+  --record <FILE>      Record all input into a file when the game closes
+  --replay <FILE>      Play back input recorded with --record
+  -h, --help           Print this and quit
+
+Options given here win over the settings file but are not saved into it.";
+
+/// Command line options. Everything but config, record and replay
+/// overrides a value from the Settings file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CliArgs {
+    pub config: PathBuf,
+    pub help: bool,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub vsync: Option<bool>,
+    pub max_fps: Option<u32>,
+    pub power: Option<PowerMode>,
+    pub backend: Option<BackendMode>,
+    pub zoom_mode: Option<ZoomMode>,
+    pub log_level: Option<String>,
+    pub themes: Option<PathBuf>,
+    pub locales: Option<PathBuf>,
+    pub sound_cues: Option<PathBuf>,
+    pub bindings: Option<PathBuf>,
+    /// Where to write the input recording. Not a setting.
+    pub record: Option<PathBuf>,
+    /// Input recording to play back. Not a setting.
+    pub replay: Option<PathBuf>,
+}
+
+impl Default for CliArgs {
+    fn default() -> Self {
+        Self {
+            config: PathBuf::from("settings.toml"),
+            help: false,
+            width: None,
+            height: None,
+            vsync: None,
+            max_fps: None,
+            power: None,
+            backend: None,
+            zoom_mode: None,
+            log_level: None,
+            themes: None,
+            locales: None,
+            sound_cues: None,
+            bindings: None,
+            record: None,
+            replay: None,
+        }
+    }
+}
+
+impl CliArgs {
+    /// Parses the arguments without the program name. Values can follow
+    /// their flag or be joined to it with =.
+    pub fn parse(
+        args: impl IntoIterator<Item = String>,
+    ) -> Result<Self, AscendingError> {
+        let mut result = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let (flag, joined) = match arg.split_once('=') {
+                Some((flag, value)) => {
+                    (flag.to_owned(), Some(value.to_owned()))
+                }
+                None => (arg, None),
+            };
+
+            if flag == "-h" || flag == "--help" {
+                result.help = true;
+                continue;
+            }
+
+            let value = match joined.or_else(|| args.next()) {
+                Some(value) => value,
+                None => {
+                    return Err(cli_error(&format!("{} needs a value", flag)))
+                }
+            };
+
+            match flag.as_str() {
+                "--config" => result.config = PathBuf::from(value),
+                "--width" => result.width = Some(parse_number(&flag, &value)?),
+                "--height" => {
+                    result.height = Some(parse_number(&flag, &value)?)
+                }
+                "--vsync" => result.vsync = Some(parse_switch(&flag, &value)?),
+                "--max-fps" => {
+                    result.max_fps = Some(parse_number(&flag, &value)?)
+                }
+                "--power" => result.power = Some(parse_name(&flag, &value)?),
+                "--backend" => {
+                    result.backend = Some(parse_name(&flag, &value)?)
+                }
+                "--zoom" => result.zoom_mode = Some(parse_name(&flag, &value)?),
+                "--log-level" => result.log_level = Some(value),
+                "--themes" => result.themes = Some(PathBuf::from(value)),
+                "--locales" => result.locales = Some(PathBuf::from(value)),
+                "--sound-cues" => {
+                    result.sound_cues = Some(PathBuf::from(value))
+                }
+                "--bindings" => result.bindings = Some(PathBuf::from(value)),
+                "--record" => result.record = Some(PathBuf::from(value)),
+                "--replay" => result.replay = Some(PathBuf::from(value)),
+                _ => {
+                    return Err(cli_error(&format!("unknown option {}", flag)))
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Overrides the Settings with every option that was given.
+    pub fn apply(&self, settings: &mut Settings) {
+        let graphics = &mut settings.graphics;
+        let paths = &mut settings.paths;
+
+        if let Some(width) = self.width {
+            graphics.width = width;
+        }
+        if let Some(height) = self.height {
+            graphics.height = height;
+        }
+        if let Some(vsync) = self.vsync {
+            graphics.vsync = vsync;
+        }
+        if let Some(max_fps) = self.max_fps {
+            graphics.max_fps = max_fps;
+        }
+        if let Some(power) = self.power {
+            graphics.power = power;
+        }
+        if let Some(backend) = self.backend {
+            graphics.backend = backend;
+        }
+        if let Some(zoom_mode) = self.zoom_mode {
+            graphics.zoom_mode = zoom_mode;
+        }
+        if let Some(level) = &self.log_level {
+            settings.logging.level = level.clone();
+        }
+        if let Some(themes) = &self.themes {
+            paths.themes = themes.clone();
+        }
+        if let Some(locales) = &self.locales {
+            paths.locales = locales.clone();
+        }
+        if let Some(sound_cues) = &self.sound_cues {
+            paths.sound_cues = sound_cues.clone();
+        }
+        if let Some(bindings) = &self.bindings {
+            paths.bindings = bindings.clone();
+        }
+    }
+}
+
+fn cli_error(message: &str) -> AscendingError {
+    AscendingError::Other(OtherError::new(&format!("{}\n\n{}", message, USAGE)))
+}
+
+fn parse_number(flag: &str, value: &str) -> Result<u32, AscendingError> {
+    value.parse().map_err(|_| {
+        cli_error(&format!("{} expects a whole number, got {}", flag, value))
+    })
+}
+
+fn parse_switch(flag: &str, value: &str) -> Result<bool, AscendingError> {
+    match value {
+        "on" | "true" => Ok(true),
+        "off" | "false" => Ok(false),
+        _ => Err(cli_error(&format!(
+            "{} expects on or off, got {}",
+            flag, value
+        ))),
+    }
+}
+
+/// Parses value the same way the Settings file names it.
+fn parse_name<T: DeserializeOwned>(
+    flag: &str,
+    value: &str,
+) -> Result<T, AscendingError> {
+    toml::Value::String(value.to_owned())
+        .try_into()
+        .map_err(|e| {
+            cli_error(&format!("{} does not accept {}: {}", flag, value, e))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliArgs, AscendingError> {
+        CliArgs::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn error(args: &[&str]) -> String {
+        parse(args).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn values_follow_or_join_their_flag() {
+        let joined =
+            parse(&["--width=800", "--vsync=off", "--zoom=fractional"]);
+        let split = parse(&[
+            "--width",
+            "800",
+            "--vsync",
+            "off",
+            "--zoom",
+            "fractional",
+        ]);
+        let joined = joined.unwrap();
+
+        assert_eq!(joined, split.unwrap());
+        assert_eq!(joined.width, Some(800));
+        assert_eq!(joined.vsync, Some(false));
+        assert_eq!(joined.zoom_mode, Some(ZoomMode::Fractional));
+        assert_eq!(joined.config, PathBuf::from("settings.toml"));
+        assert!(parse(&["--config", "a=b.toml", "-h"]).unwrap().help);
+    }
+
+    #[test]
+    fn bad_arguments_are_reported() {
+        assert!(error(&["--width"]).starts_with("--width needs a value"));
+        assert!(error(&["--fullscreen", "on"])
+            .starts_with("unknown option --fullscreen"));
+        assert!(error(&["--height=tall"])
+            .starts_with("--height expects a whole number, got tall"));
+        assert!(error(&["--vsync", "maybe"])
+            .starts_with("--vsync expects on or off, got maybe"));
+        assert!(error(&["--power", "turbo"])
+            .starts_with("--power does not accept turbo"));
+        assert!(error(&["--zoom"]).contains("Usage: game [OPTIONS]"));
+    }
+}
@@ -0,0 +1,171 @@
+use cosmic_text::{Attrs, Family};
+use graphics::*;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::func::{load_toml, load_toml_dir};
+use crate::{TextureAllocation, TILE_SIZE};
+
+/// Colors as rgba bytes so they read well in the theme files.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Palette {
+    /// What the screen gets cleared too before anything renders.
+    pub clear: [u8; 4],
+    /// Ship counters and status text.
+    pub text: [u8; 4],
+    /// FPS and other debug text.
+    pub debug_text: [u8; 4],
+    /// Shade over the board that is waiting for its turn.
+    pub shade: [u8; 4],
+}
+
+impl Palette {
+    pub fn color(rgba: [u8; 4]) -> Color {
+        Color::rgba(rgba[0], rgba[1], rgba[2], rgba[3])
+    }
+
+    pub fn clear_color(&self) -> wgpu::Color {
+        wgpu::Color {
+            r: self.clear[0] as f64 / 255.0,
+            g: self.clear[1] as f64 / 255.0,
+            b: self.clear[2] as f64 / 255.0,
+            a: self.clear[3] as f64 / 255.0,
+        }
+    }
+}
+
+/// Image paths for everything the game draws.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ThemeTextures {
+    pub ship: String,
+    pub icon: String,
+    pub explosion: String,
+    pub background: String,
+    pub result: String,
+    pub white: String,
+    /// Tilesheet for the water of the boards.
+    pub tiles: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FontFile {
+    /// Font file loaded into the renderers font system.
+    pub path: String,
+    /// Family name within the font file to render with.
+    pub family: String,
+}
+
+impl FontFile {
+    /// Loads the font unless its family is already within the font system,
+    /// so swapping back and forth does not pile up copies of it.
+    pub fn load(
+        &self,
+        renderer: &mut GpuRenderer,
+    ) -> Result<(), AscendingError> {
+        let db = renderer.font_sys_mut().db_mut();
+        let loaded = db.faces().any(|face| {
+            face.families.iter().any(|(name, _)| name == &self.family)
+        });
+
+        if !loaded {
+            db.load_font_file(&self.path)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Theme {
+    pub name: String,
+    pub palette: Palette,
+    pub textures: ThemeTextures,
+    /// Uses the systems default font if not set.
+    #[serde(default)]
+    pub font: Option<FontFile>,
+}
+
+impl Theme {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AscendingError> {
+        load_toml(path)
+    }
+
+    /// Loads every theme within the folder sorted by file name. Themes that
+    /// fail to load are logged and skipped.
+    pub fn load_all(
+        dir: impl AsRef<Path>,
+    ) -> Result<Vec<Self>, AscendingError> {
+        load_toml_dir(dir)
+    }
+
+    pub fn attrs(&self) -> Attrs<'_> {
+        match &self.font {
+            Some(font) => Attrs::new().family(Family::Name(&font.family)),
+            None => Attrs::new(),
+        }
+    }
+
+    /// Family name of the font for things that keep it around.
+    pub fn font_family(&self) -> Option<String> {
+        self.font.as_ref().map(|font| font.family.clone())
+    }
+
+    /// Clears both atlases and uploads this themes textures and font.
+    /// Everything holding an old Allocation must be given the new ones.
+    /// Files are all read first so a broken theme leaves the atlases alone.
+    pub fn upload(
+        &self,
+        image_atlas: &mut AtlasGroup,
+        map_atlas: &mut AtlasGroup,
+        renderer: &mut GpuRenderer,
+    ) -> Result<TextureAllocation, AscendingError> {
+        let textures = &self.textures;
+        let images = [
+            Texture::from_file(&textures.ship)?,
+            Texture::from_file(&textures.icon)?,
+            Texture::from_file(&textures.explosion)?,
+            Texture::from_file(&textures.background)?,
+            Texture::from_file(&textures.result)?,
+            Texture::from_file(&textures.white)?,
+        ];
+        let tiles = Texture::from_file(&textures.tiles)?;
+
+        if let Some(font) = &self.font {
+            font.load(renderer)?;
+        }
+
+        image_atlas.clear();
+        map_atlas.clear();
+
+        let mut allocations = Vec::with_capacity(images.len());
+        for image in &images {
+            allocations.push(
+                image
+                    .group_upload(image_atlas, renderer)
+                    .ok_or_else(|| OtherError::new("failed to upload image"))?,
+            );
+        }
+
+        tiles
+            .new_tilesheet(map_atlas, renderer, TILE_SIZE)
+            .ok_or_else(|| OtherError::new("failed to upload tiles"))?;
+
+        Ok(TextureAllocation {
+            ship_texture: allocations[0],
+            icon_texture: allocations[1],
+            explosion_texture: allocations[2],
+            game_bg_texture: allocations[3],
+            result_texture: allocations[4],
+            white_texture: allocations[5],
+        })
+    }
+}
+
+/// Attrs for a font family kept from Theme::font_family. Free standing so
+/// it can be used while the Text holding it is borrowed.
+pub fn font_attrs(family: &Option<String>) -> Attrs<'_> {
+    match family {
+        Some(family) => Attrs::new().family(Family::Name(family)),
+        None => Attrs::new(),
+    }
+}
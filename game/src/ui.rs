@@ -1,3 +1,9 @@
+mod chat;
+mod options;
 mod test;
+mod text_edit;
 
-pub use test::*;
\ No newline at end of file
+pub use chat::*;
+pub use options::*;
+pub use test::*;
+pub use text_edit::*;
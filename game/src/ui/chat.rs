@@ -0,0 +1,392 @@
+use cosmic_text::{Buffer, Wrap};
+use graphics::*;
+use input::{InputHandler, MouseAxis};
+use std::hash::Hash;
+use winit::event::VirtualKeyCode;
+
+use super::TextEdit;
+use crate::layout::{Rect, ScreenLayout};
+use crate::theme::{font_attrs, Palette, Theme};
+use crate::{
+    TextureAllocation, GUI_CHAT_CARET_ORDER, GUI_CHAT_ORDER,
+    GUI_CHAT_SELECTION_ORDER,
+};
+
+/// Messages kept in the scrollback. Older ones are dropped.
+pub const CHAT_LOG_SIZE: usize = 100;
+/// Chars a single message can hold.
+pub const CHAT_MAX_CHARS: usize = 120;
+/// Seconds the panel stays up after a message comes in while closed.
+pub const CHAT_SHOW_TIME: f32 = 5.0;
+/// Lines moved by one step of the mouse wheel.
+const WHEEL_LINES: f32 = 3.0;
+
+/// Chat scrollback with a single line text field under it.
+pub struct ChatPanel {
+    /// Typing goes to the text field while set.
+    pub focused: bool,
+    pub edit: TextEdit,
+    /// What the IME is composing, shown at the caret until it commits.
+    pub composition: String,
+    pub background: Image,
+    pub selection: Image,
+    pub caret: Image,
+    pub log: Text,
+    pub input: Text,
+    pub messages: Vec<String>,
+    /// First line of the log shown.
+    pub scroll: i32,
+    /// Keeps the newest message in view until scrolled up.
+    pub follow: bool,
+    /// Part of a line scrolled by the wheel that is not shown yet.
+    wheel_rest: f32,
+    /// How far the text field is moved left to keep the caret in view.
+    pub input_offset: f32,
+    /// Seconds the panel stays up till while not focused.
+    pub show_until: f32,
+    pub font_family: Option<String>,
+    /// Messages sent from the text field that have not gone out yet. The
+    /// match connection takes them with take_outgoing.
+    outgoing: Vec<String>,
+    /// Screen pixels the text field is clipped to.
+    input_bounds: Rect,
+    zoom: f32,
+}
+
+impl ChatPanel {
+    pub fn new(
+        resource: &TextureAllocation,
+        renderer: &mut GpuRenderer,
+        layout: &ScreenLayout,
+        theme: &Theme,
+    ) -> Self {
+        let mut result = Self {
+            focused: false,
+            edit: TextEdit::new(CHAT_MAX_CHARS),
+            composition: String::new(),
+            background: Image::new(Some(resource.white_texture), renderer, 1),
+            selection: Image::new(Some(resource.white_texture), renderer, 1),
+            caret: Image::new(Some(resource.white_texture), renderer, 1),
+            log: Text::new(
+                renderer,
+                Some(layout.font_metrics()),
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec2::new(292.0, 74.0),
+            ),
+            input: Text::new(
+                renderer,
+                Some(layout.font_metrics()),
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec2::new(292.0, 12.0),
+            ),
+            messages: Vec::new(),
+            scroll: 0,
+            wheel_rest: 0.0,
+            follow: true,
+            input_offset: 0.0,
+            show_until: 0.0,
+            font_family: None,
+            outgoing: Vec::new(),
+            input_bounds: Rect::default(),
+            zoom: layout.zoom,
+        };
+
+        for image in [
+            &mut result.background,
+            &mut result.selection,
+            &mut result.caret,
+        ] {
+            image.uv = Vec4::new(0.0, 0.0, 16.0, 16.0);
+        }
+
+        result.log.set_wrap(renderer, Wrap::Word);
+        result.input.set_wrap(renderer, Wrap::None);
+        result.set_theme(theme, resource, renderer);
+        result.set_layout(layout, renderer);
+        result
+    }
+
+    pub fn set_layout(
+        &mut self,
+        layout: &ScreenLayout,
+        renderer: &mut GpuRenderer,
+    ) {
+        let rect = layout.chat;
+        self.background.pos = Vec3::new(rect.pos.x, rect.pos.y, GUI_CHAT_ORDER);
+        self.background.hw = rect.size;
+        self.background.changed = true;
+
+        layout.place_text_area(&mut self.log, &layout.chat_log, renderer);
+        layout.place_text(&mut self.input, &layout.chat_input, renderer);
+        self.input_bounds = layout.chat_input.bounds;
+        self.zoom = layout.zoom;
+
+        self.refresh_log(renderer);
+        self.refresh_input(renderer);
+    }
+
+    pub fn set_theme(
+        &mut self,
+        theme: &Theme,
+        resource: &TextureAllocation,
+        renderer: &mut GpuRenderer,
+    ) {
+        let text = theme.palette.text;
+
+        self.background.color = Palette::color(theme.palette.shade);
+        self.selection.color = Palette::color([text[0], text[1], text[2], 96]);
+        self.caret.color = Palette::color(text);
+        for image in
+            [&mut self.background, &mut self.selection, &mut self.caret]
+        {
+            image.texture = Some(resource.white_texture);
+            image.changed = true;
+        }
+
+        self.log.set_default_color(Palette::color(text));
+        self.input.set_default_color(Palette::color(text));
+        self.font_family = theme.font_family();
+
+        self.refresh_log(renderer);
+        self.refresh_input(renderer);
+    }
+
+    /// Adds a line to the scrollback and shows the panel for a while.
+    pub fn push_message(
+        &mut self,
+        message: String,
+        seconds: f32,
+        renderer: &mut GpuRenderer,
+    ) {
+        self.messages.push(message);
+        if self.messages.len() > CHAT_LOG_SIZE {
+            let extra = self.messages.len() - CHAT_LOG_SIZE;
+            self.messages.drain(..extra);
+        }
+
+        self.show_until = seconds + CHAT_SHOW_TIME;
+        self.refresh_log(renderer);
+    }
+
+    pub fn is_visible(&self, seconds: f32) -> bool {
+        self.focused || seconds < self.show_until
+    }
+
+    pub fn open(&mut self) {
+        self.focused = true;
+        self.background.changed = true;
+    }
+
+    /// Keeps the panel up for a moment so the last message can be read.
+    pub fn close(&mut self, seconds: f32) {
+        self.focused = false;
+        self.composition.clear();
+        self.show_until = seconds + CHAT_SHOW_TIME;
+    }
+
+    /// Edits the text field with the keys pressed this frame. Return queues
+    /// the message to be sent. Return and Escape both close the panel.
+    /// Keys are left to the IME while it is composing.
+    pub fn handle_input<A, X>(
+        &mut self,
+        input_handler: &InputHandler<A, X>,
+        seconds: f32,
+        renderer: &mut GpuRenderer,
+    ) where
+        A: Clone + Eq + Hash + Send + Sync,
+        X: Clone + Eq + Hash + Send + Sync,
+    {
+        let modifiers = input_handler.modifiers();
+        let select = modifiers.shift();
+        let before = (
+            self.edit.text.clone(),
+            self.edit.caret,
+            self.edit.anchor,
+            self.composition.clone(),
+        );
+        let composing = !self.composition.is_empty();
+
+        for key in input_handler.key_presses() {
+            if composing {
+                break;
+            }
+
+            match key {
+                VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => {
+                    self.outgoing.extend(self.edit.submit());
+                    self.close(seconds);
+                }
+                VirtualKeyCode::Escape => {
+                    self.edit.clear();
+                    self.close(seconds);
+                }
+                VirtualKeyCode::Back => self.edit.backspace(),
+                VirtualKeyCode::Delete => self.edit.delete(),
+                VirtualKeyCode::Left => self.edit.move_left(select),
+                VirtualKeyCode::Right => self.edit.move_right(select),
+                VirtualKeyCode::Home => self.edit.move_home(select),
+                VirtualKeyCode::End => self.edit.move_end(select),
+                VirtualKeyCode::Up => self.edit.history_prev(),
+                VirtualKeyCode::Down => self.edit.history_next(),
+                VirtualKeyCode::PageUp => {
+                    let lines = self.page_lines();
+                    self.scroll_by(-lines, renderer);
+                }
+                VirtualKeyCode::PageDown => {
+                    let lines = self.page_lines();
+                    self.scroll_by(lines, renderer);
+                }
+                VirtualKeyCode::A if modifiers.ctrl() => self.edit.select_all(),
+                _ => {}
+            }
+
+            if !self.focused {
+                break;
+            }
+        }
+
+        if self.focused {
+            // Holding ctrl types control characters which insert skips.
+            self.edit.insert(input_handler.characters());
+            self.composition = input_handler
+                .composition()
+                .map_or_else(String::new, |composition| {
+                    composition.text.clone()
+                });
+
+            // Touchpads scroll by parts of a line which add up over frames.
+            self.wheel_rest -= input_handler
+                .mouse_wheel_value(MouseAxis::Vertical)
+                * WHEEL_LINES;
+            let lines = self.wheel_rest.trunc();
+            if lines != 0.0 {
+                self.wheel_rest -= lines;
+                self.scroll_by(lines as i32, renderer);
+            }
+        }
+
+        if before
+            != (
+                self.edit.text.clone(),
+                self.edit.caret,
+                self.edit.anchor,
+                self.composition.clone(),
+            )
+        {
+            self.refresh_input(renderer);
+        }
+    }
+
+    /// Takes the messages queued to be sent, oldest first.
+    pub fn take_outgoing(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.outgoing)
+    }
+
+    /// Scrolls the log by lines, negative going up towards older messages.
+    pub fn scroll_by(&mut self, lines: i32, renderer: &mut GpuRenderer) {
+        let max_scroll = self.max_scroll(renderer);
+
+        self.scroll = (self.scroll + lines).clamp(0, max_scroll);
+        self.follow = self.scroll == max_scroll;
+        self.log.set_scroll(renderer, self.scroll);
+    }
+
+    fn page_lines(&mut self) -> i32 {
+        (self.log.get_text_buffer().visible_lines() - 1).max(1)
+    }
+
+    /// Lines that can be scrolled past before the last one is at the bottom.
+    fn max_scroll(&mut self, renderer: &mut GpuRenderer) -> i32 {
+        let buffer = self.log.get_text_buffer();
+        let lines = buffer.shape_until(renderer.font_sys_mut(), i32::MAX);
+
+        (lines - buffer.visible_lines()).max(0)
+    }
+
+    fn refresh_log(&mut self, renderer: &mut GpuRenderer) {
+        self.log.set_text(
+            renderer,
+            &self.messages.join("\n"),
+            font_attrs(&self.font_family),
+        );
+
+        let max_scroll = self.max_scroll(renderer);
+        let scroll = if self.follow {
+            max_scroll
+        } else {
+            self.scroll.min(max_scroll)
+        };
+
+        self.scroll = scroll;
+        self.log.set_scroll(renderer, scroll);
+    }
+
+    /// Window position in physical pixels from the top left for the IME to
+    /// show its candidates at, just under the caret.
+    pub fn ime_position(&self, window_height: f32) -> (f32, f32) {
+        (
+            self.caret.pos.x * self.zoom,
+            window_height - self.input.pos.y,
+        )
+    }
+
+    /// Rewrites the text field and moves the caret and selection to match.
+    /// Any composition is shown at the caret with the caret after it.
+    fn refresh_input(&mut self, renderer: &mut GpuRenderer) {
+        let mut text = self.edit.text.clone();
+        text.insert_str(self.edit.caret, &self.composition);
+        self.input
+            .set_text(renderer, &text, font_attrs(&self.font_family));
+
+        let buffer = self.input.get_text_buffer();
+        let caret_x = glyph_x(buffer, self.edit.caret + self.composition.len());
+        let selection_x = self
+            .edit
+            .selection()
+            .filter(|_| self.composition.is_empty())
+            .map(|range| {
+                (glyph_x(buffer, range.start), glyph_x(buffer, range.end))
+            });
+
+        // Slide the text so the caret stays within the field.
+        let inset = self.input.pos.x - self.input_bounds.pos.x;
+        let width = (self.input_bounds.size.x - inset * 2.0).max(0.0);
+        if caret_x - self.input_offset > width {
+            self.input_offset = caret_x - width;
+        } else if caret_x < self.input_offset {
+            self.input_offset = caret_x;
+        }
+        self.input.set_offset(Vec2::new(-self.input_offset, 0.0));
+
+        // Images use the camera so pixels get turned into sprite units.
+        let left = self.input.pos.x - self.input_offset;
+        let height = self.input.size.y / self.zoom;
+        let y = self.input.pos.y / self.zoom;
+
+        self.caret.pos =
+            Vec3::new((left + caret_x) / self.zoom, y, GUI_CHAT_CARET_ORDER);
+        self.caret.hw = Vec2::new(1.0, height);
+        self.caret.changed = true;
+
+        if let Some((start, end)) = selection_x {
+            let start = (left + start).max(self.input_bounds.pos.x);
+            let end = (left + end).min(self.input_bounds.right());
+
+            self.selection.pos =
+                Vec3::new(start / self.zoom, y, GUI_CHAT_SELECTION_ORDER);
+            self.selection.hw =
+                Vec2::new((end - start).max(0.0) / self.zoom, height);
+            self.selection.changed = true;
+        }
+    }
+}
+
+/// Pixels from the start of the first line to the char at index.
+fn glyph_x(buffer: &Buffer, index: usize) -> f32 {
+    buffer.layout_runs().next().map_or(0.0, |run| {
+        run.glyphs
+            .iter()
+            .find(|glyph| glyph.start >= index)
+            .map_or(run.line_w, |glyph| glyph.x)
+    })
+}
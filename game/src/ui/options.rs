@@ -0,0 +1,204 @@
+use graphics::*;
+
+use crate::accessibility::Accessibility;
+use crate::func::cycle_index;
+use crate::layout::ScreenLayout;
+use crate::locale::Locale;
+use crate::theme::{Palette, Theme};
+use crate::{TextureAllocation, GUI_OPTIONS_ORDER};
+
+/// Rows of the options menu that can be changed with Prev and Next.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OptionsRow {
+    Theme,
+    Language,
+    Colors,
+    HighContrast,
+    TextSize,
+    ReducedMotion,
+}
+
+impl OptionsRow {
+    /// Every row from the top of the menu down.
+    pub const ALL: [OptionsRow; 6] = [
+        OptionsRow::Theme,
+        OptionsRow::Language,
+        OptionsRow::Colors,
+        OptionsRow::HighContrast,
+        OptionsRow::TextSize,
+        OptionsRow::ReducedMotion,
+    ];
+
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    /// The rows text in the Locale's language.
+    pub fn message(
+        &self,
+        theme: &Theme,
+        locale: &Locale,
+        accessibility: &Accessibility,
+    ) -> String {
+        let on_off = |on: bool| locale.text(if on { "on" } else { "off" }, &[]);
+
+        match self {
+            OptionsRow::Theme => {
+                locale.text("options-theme", &[("name", &theme.name)])
+            }
+            OptionsRow::Language => {
+                locale.text("options-language", &[("name", &locale.name)])
+            }
+            OptionsRow::Colors => {
+                let name =
+                    locale.text(accessibility.color_mode.message_key(), &[]);
+                locale.text("options-colors", &[("name", &name)])
+            }
+            OptionsRow::HighContrast => locale.text(
+                "options-contrast",
+                &[("value", &on_off(accessibility.high_contrast))],
+            ),
+            OptionsRow::TextSize => {
+                let percent = (accessibility.text_scale * 100.0).round();
+                locale.text("options-text-size", &[("value", &percent)])
+            }
+            OptionsRow::ReducedMotion => locale.text(
+                "options-motion",
+                &[("value", &on_off(accessibility.reduced_motion))],
+            ),
+        }
+    }
+}
+
+/// Overlay for picking game options such as the Theme and Language.
+pub struct OptionsMenu {
+    pub visible: bool,
+    pub selected: OptionsRow,
+    pub background: Image,
+    pub title: Text,
+    /// One Text per OptionsRow in the same order.
+    pub rows: [Text; 6],
+}
+
+impl OptionsMenu {
+    pub fn new(
+        resource: &TextureAllocation,
+        renderer: &mut GpuRenderer,
+        layout: &ScreenLayout,
+        theme: &Theme,
+        locale: &Locale,
+        accessibility: &Accessibility,
+    ) -> Self {
+        let mut result = Self {
+            visible: false,
+            selected: OptionsRow::Theme,
+            background: Image::new(Some(resource.white_texture), renderer, 1),
+            title: Text::new(
+                renderer,
+                Some(layout.font_metrics()),
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec2::new(184.0, 16.0),
+            ),
+            rows: [(); 6].map(|_| {
+                Text::new(
+                    renderer,
+                    Some(layout.font_metrics()),
+                    Vec3::new(0.0, 0.0, 0.0),
+                    Vec2::new(184.0, 16.0),
+                )
+            }),
+        };
+
+        result.background.uv = Vec4::new(0.0, 0.0, 16.0, 16.0);
+        result.set_theme(theme, locale, accessibility, resource, renderer);
+        result.set_layout(layout, renderer);
+        result
+    }
+
+    pub fn set_layout(
+        &mut self,
+        layout: &ScreenLayout,
+        renderer: &mut GpuRenderer,
+    ) {
+        let rect = layout.options;
+        self.background.pos =
+            Vec3::new(rect.pos.x, rect.pos.y, GUI_OPTIONS_ORDER);
+        self.background.hw = rect.size;
+        self.background.changed = true;
+
+        layout.place_text(&mut self.title, &layout.options_title, renderer);
+        for (text, rect) in self.rows.iter_mut().zip(&layout.options_rows) {
+            layout.place_text(text, rect, renderer);
+        }
+    }
+
+    /// Recolors the menu and shows the Theme as the one selected.
+    pub fn set_theme(
+        &mut self,
+        theme: &Theme,
+        locale: &Locale,
+        accessibility: &Accessibility,
+        resource: &TextureAllocation,
+        renderer: &mut GpuRenderer,
+    ) {
+        let color = Palette::color(theme.palette.text);
+
+        self.background.texture = Some(resource.white_texture);
+        self.background.color = Palette::color(theme.palette.shade);
+        self.background.changed = true;
+
+        self.title.set_default_color(color);
+        for text in &mut self.rows {
+            text.set_default_color(color);
+        }
+        self.set_text(theme, locale, accessibility, renderer);
+    }
+
+    /// Writes out every row in the Locale's language.
+    pub fn set_text(
+        &mut self,
+        theme: &Theme,
+        locale: &Locale,
+        accessibility: &Accessibility,
+        renderer: &mut GpuRenderer,
+    ) {
+        let attrs = theme.attrs();
+
+        self.title.set_text(
+            renderer,
+            &locale.text("options-title", &[]),
+            attrs,
+        );
+        for row in OptionsRow::ALL {
+            let message = row.message(theme, locale, accessibility);
+            let marker = if self.selected == row { ">" } else { " " };
+
+            self.rows[row.index()].set_text(
+                renderer,
+                &format!("{} {}", marker, message),
+                attrs,
+            );
+        }
+    }
+
+    /// Moves the selection up or down by step rows, wrapping around.
+    pub fn select_row(
+        &mut self,
+        step: isize,
+        theme: &Theme,
+        locale: &Locale,
+        accessibility: &Accessibility,
+        renderer: &mut GpuRenderer,
+    ) {
+        let index =
+            cycle_index(self.selected.index(), step, OptionsRow::ALL.len());
+
+        self.selected = OptionsRow::ALL[index];
+        self.set_text(theme, locale, accessibility, renderer);
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+        self.background.changed = true;
+    }
+}
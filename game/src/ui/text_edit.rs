@@ -0,0 +1,281 @@
+use std::ops::Range;
+
+/// Single line of editable text with a caret, a selection and a history of
+/// what was submitted. Positions are byte indexes that always sit on a char
+/// boundary.
+#[derive(Clone, Debug, Default)]
+pub struct TextEdit {
+    pub text: String,
+    pub caret: usize,
+    /// Other end of the selection from the caret if anything is selected.
+    pub anchor: Option<usize>,
+    /// Limit in chars. 0 means no limit.
+    pub max_chars: usize,
+    history: Vec<String>,
+    /// Entry of history being shown while going through it.
+    history_index: Option<usize>,
+    /// What was typed before going through history, restored after.
+    draft: String,
+}
+
+impl TextEdit {
+    pub fn new(max_chars: usize) -> Self {
+        Self {
+            max_chars,
+            ..Self::default()
+        }
+    }
+
+    pub fn selection(&self) -> Option<Range<usize>> {
+        let anchor = self.anchor?;
+        (anchor != self.caret)
+            .then(|| anchor.min(self.caret)..anchor.max(self.caret))
+    }
+
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.caret = 0;
+        self.anchor = None;
+        self.history_index = None;
+    }
+
+    /// Replaces the selection with text or inserts it at the caret.
+    /// Control characters are skipped and text is cut off at max_chars.
+    pub fn insert(&mut self, text: &str) {
+        self.delete_selection();
+
+        let mut room = if self.max_chars == 0 {
+            usize::MAX
+        } else {
+            self.max_chars.saturating_sub(self.text.chars().count())
+        };
+
+        for character in text.chars().filter(|c| !c.is_control()) {
+            if room == 0 {
+                break;
+            }
+
+            self.text.insert(self.caret, character);
+            self.caret += character.len_utf8();
+            room -= 1;
+        }
+    }
+
+    /// Deletes the selection or the char before the caret.
+    pub fn backspace(&mut self) {
+        if !self.delete_selection() && self.caret > 0 {
+            let start = self.prev_boundary(self.caret);
+            self.text.replace_range(start..self.caret, "");
+            self.caret = start;
+        }
+    }
+
+    /// Deletes the selection or the char after the caret.
+    pub fn delete(&mut self) {
+        if !self.delete_selection() && self.caret < self.text.len() {
+            let end = self.next_boundary(self.caret);
+            self.text.replace_range(self.caret..end, "");
+        }
+    }
+
+    pub fn move_left(&mut self, select: bool) {
+        match self.selection() {
+            Some(range) if !select => self.move_to(range.start, false),
+            _ => self.move_to(self.prev_boundary(self.caret), select),
+        }
+    }
+
+    pub fn move_right(&mut self, select: bool) {
+        match self.selection() {
+            Some(range) if !select => self.move_to(range.end, false),
+            _ => self.move_to(self.next_boundary(self.caret), select),
+        }
+    }
+
+    pub fn move_home(&mut self, select: bool) {
+        self.move_to(0, select);
+    }
+
+    pub fn move_end(&mut self, select: bool) {
+        self.move_to(self.text.len(), select);
+    }
+
+    pub fn select_all(&mut self) {
+        self.anchor = Some(0);
+        self.caret = self.text.len();
+    }
+
+    /// Shows the entry submitted before the one showing.
+    pub fn history_prev(&mut self) {
+        let index = match self.history_index {
+            Some(0) => return,
+            Some(index) => index - 1,
+            None if self.history.is_empty() => return,
+            None => {
+                self.draft = self.text.clone();
+                self.history.len() - 1
+            }
+        };
+
+        self.history_index = Some(index);
+        self.set_text(self.history[index].clone());
+    }
+
+    /// Shows the entry submitted after the one showing or what was being
+    /// typed before going through history.
+    pub fn history_next(&mut self) {
+        let Some(index) = self.history_index else {
+            return;
+        };
+
+        if index + 1 < self.history.len() {
+            self.history_index = Some(index + 1);
+            self.set_text(self.history[index + 1].clone());
+        } else {
+            let draft = std::mem::take(&mut self.draft);
+            self.history_index = None;
+            self.set_text(draft);
+        }
+    }
+
+    /// Takes the text, adding it to history. Returns None if it was blank.
+    pub fn submit(&mut self) -> Option<String> {
+        let text = self.text.trim().to_owned();
+        self.clear();
+
+        if text.is_empty() {
+            return None;
+        }
+
+        if self.history.last() != Some(&text) {
+            self.history.push(text.clone());
+        }
+
+        Some(text)
+    }
+
+    fn set_text(&mut self, text: String) {
+        self.text = text;
+        self.caret = self.text.len();
+        self.anchor = None;
+    }
+
+    fn move_to(&mut self, index: usize, select: bool) {
+        if select {
+            self.anchor.get_or_insert(self.caret);
+        } else {
+            self.anchor = None;
+        }
+
+        self.caret = index;
+    }
+
+    fn delete_selection(&mut self) -> bool {
+        let Some(range) = self.selection() else {
+            self.anchor = None;
+            return false;
+        };
+
+        self.caret = range.start;
+        self.anchor = None;
+        self.text.replace_range(range, "");
+        true
+    }
+
+    fn prev_boundary(&self, index: usize) -> usize {
+        self.text[..index]
+            .char_indices()
+            .next_back()
+            .map_or(0, |(i, _)| i)
+    }
+
+    fn next_boundary(&self, index: usize) -> usize {
+        self.text[index..]
+            .chars()
+            .next()
+            .map_or(index, |c| index + c.len_utf8())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(text: &str) -> TextEdit {
+        let mut edit = TextEdit::new(0);
+        edit.insert(text);
+        edit
+    }
+
+    #[test]
+    fn multibyte_chars_are_edited_whole() {
+        let mut edit = edit("héllo日本");
+        edit.backspace();
+        assert_eq!(edit.text, "héllo日");
+        assert_eq!(edit.caret, edit.text.len());
+
+        edit.move_home(false);
+        edit.move_right(false);
+        edit.delete();
+        assert_eq!(edit.text, "hllo日");
+
+        edit.move_end(false);
+        edit.move_left(false);
+        edit.insert("ö");
+        assert_eq!(edit.text, "hlloö日");
+        assert!(edit.text.is_char_boundary(edit.caret));
+    }
+
+    #[test]
+    fn typing_replaces_the_selection() {
+        let mut edit = edit("hello world");
+        edit.move_home(false);
+        (0..5).for_each(|_| edit.move_right(true));
+        assert_eq!(edit.selection(), Some(0..5));
+
+        edit.insert("bye");
+        assert_eq!(edit.text, "bye world");
+        assert_eq!((edit.caret, edit.selection()), (3, None));
+
+        edit.select_all();
+        edit.backspace();
+        assert_eq!((edit.text.as_str(), edit.caret), ("", 0));
+    }
+
+    #[test]
+    fn max_chars_cuts_off_the_rest() {
+        let mut edit = TextEdit::new(3);
+        edit.insert("日本語です");
+        assert_eq!(edit.text, "日本語");
+        edit.insert("a");
+        assert_eq!(edit.text, "日本語");
+
+        // Replacing a selection makes room first.
+        edit.move_left(true);
+        edit.insert("ab\ncd");
+        assert_eq!(edit.text, "日本a");
+    }
+
+    #[test]
+    fn history_comes_back_to_the_draft() {
+        let mut edit = TextEdit::new(0);
+        for text in ["first", "  ", "second", "second"] {
+            edit.insert(text);
+            edit.submit();
+        }
+        edit.insert("dra");
+
+        edit.history_prev();
+        assert_eq!(edit.text, "second");
+        edit.history_prev();
+        edit.history_prev();
+        assert_eq!(edit.text, "first");
+
+        edit.history_next();
+        assert_eq!(edit.text, "second");
+        edit.history_next();
+        assert_eq!((edit.text.as_str(), edit.caret), ("dra", 3));
+        edit.history_next();
+        assert_eq!(edit.text, "dra");
+    }
+}
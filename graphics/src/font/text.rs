@@ -351,6 +351,16 @@ impl Text {
         self
     }
 
+    pub fn set_metrics(
+        &mut self,
+        renderer: &mut GpuRenderer,
+        metrics: Metrics,
+    ) -> &mut Self {
+        self.buffer.set_metrics(&mut renderer.font_sys, metrics);
+        self.changed = true;
+        self
+    }
+
     pub fn set_default_color(&mut self, color: Color) -> &mut Self {
         self.default_color = color;
         self.changed = true;
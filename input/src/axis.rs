@@ -1,4 +1,5 @@
 use super::button::Button;
+use super::gamepad::{GamepadAxis, ResponseCurve};
 use serde::{Deserialize, Serialize};
 use std::hash::Hash;
 
@@ -10,6 +11,24 @@ pub enum MouseAxis {
     Vertical,
 }
 
+/// How mouse wheel motion is turned into the value of a wheel axis.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WheelSettings {
+    /// Pixels of touchpad scrolling that count as one line.
+    pub pixels_per_line: f32,
+    /// Lines are multiplied by this, such as to scroll faster.
+    pub scale: f32,
+}
+
+impl Default for WheelSettings {
+    fn default() -> Self {
+        Self {
+            pixels_per_line: 20.0,
+            scale: 1.0,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub enum Axis {
     /// An emulated axis using two buttons where the positive button maps to 1.0 and the negative
@@ -27,6 +46,23 @@ pub enum Axis {
         limit: bool,
         radius: ordered_float::NotNan<f32>,
     },
-    /// The mouse wheel as an axis.
+    /// Lines the mouse wheel moved this frame, scaled by the
+    /// WheelSettings.
     MouseWheel { axis: MouseAxis },
+    /// One finger dragging on the touch screen, read like MouseMotion.
+    TouchDrag {
+        axis: MouseAxis,
+        limit: bool,
+        radius: ordered_float::NotNan<f32>,
+    },
+    /// Two fingers spreading apart, positive, or pinching together,
+    /// negative. The change in distance is divided by radius.
+    Pinch { radius: ordered_float::NotNan<f32> },
+    /// A stick or trigger on any connected gamepad. Values inside
+    /// dead_zone read as 0.0.
+    Gamepad {
+        axis: GamepadAxis,
+        dead_zone: ordered_float::NotNan<f32>,
+        curve: ResponseCurve,
+    },
 }
@@ -1,12 +1,16 @@
 use super::axis::Axis;
 use super::button::Button;
-use serde::{Deserialize, Serialize};
+use super::error::InputError;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+use std::fs;
 use std::hash::Hash;
+use std::path::Path;
 
 /// Represents a collection of bindings mapping inputs to actions and axes for various input
 /// devices.
-#[derive(Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Bindings<ActionId, AxisId>
 where
     ActionId: Clone + Eq + Hash + Send + Sync,
@@ -61,3 +65,427 @@ where
         }
     }
 }
+
+impl<ActionId, AxisId> Bindings<ActionId, AxisId>
+where
+    ActionId: Clone + Eq + Hash + Send + Sync,
+    AxisId: Clone + Eq + Hash + Send + Sync,
+{
+    /// The button combinations bound to the action.
+    pub fn action(&self, id: &ActionId) -> &[Vec<Button>] {
+        self.actions
+            .get(id)
+            .map_or(&[], |bindings| bindings.as_slice())
+    }
+
+    /// Replaces every button combination bound to the action. An empty
+    /// list leaves the action unbound.
+    pub fn set_action(&mut self, id: ActionId, bindings: Vec<Vec<Button>>) {
+        self.actions.insert(id, bindings);
+    }
+
+    /// Binds the action to button alone.
+    pub fn rebind_action(&mut self, id: ActionId, button: Button) {
+        self.set_action(id, vec![vec![button]]);
+    }
+
+    pub fn remove_action(&mut self, id: &ActionId) {
+        self.actions.remove(id);
+    }
+
+    /// Copies over every action and axis from defaults that has no
+    /// bindings here.
+    pub fn merge_defaults(&mut self, defaults: &Self) {
+        for (id, bindings) in &defaults.actions {
+            self.actions
+                .entry(id.clone())
+                .or_insert_with(|| bindings.clone());
+        }
+
+        for (id, axes) in &defaults.axes {
+            self.axes.entry(id.clone()).or_insert_with(|| axes.clone());
+        }
+    }
+
+    /// Button combinations bound to more than one action. Combinations
+    /// holding the same buttons in a different order count as the same.
+    pub fn conflicts(&self) -> Vec<BindingProblem<ActionId>> {
+        let mut combinations: Vec<(&Vec<Button>, Vec<ActionId>)> = Vec::new();
+
+        for (id, bindings) in &self.actions {
+            for buttons in bindings.iter().filter(|buttons| !buttons.is_empty())
+            {
+                match combinations
+                    .iter_mut()
+                    .find(|(other, _)| same_buttons(other, buttons))
+                {
+                    Some((_, actions)) => {
+                        if !actions.contains(id) {
+                            actions.push(id.clone());
+                        }
+                    }
+                    None => combinations.push((buttons, vec![id.clone()])),
+                }
+            }
+        }
+
+        combinations
+            .into_iter()
+            .filter(|(_, actions)| actions.len() > 1)
+            .map(|(buttons, actions)| BindingProblem::Conflict {
+                buttons: buttons.clone(),
+                actions,
+            })
+            .collect()
+    }
+
+    /// Whether button is part of any action or emulated axis.
+    pub fn binds(&self, button: Button) -> bool {
+        self.actions
+            .values()
+            .flatten()
+            .any(|buttons| buttons.contains(&button))
+            || self.axes.values().flatten().any(|axis| {
+                matches!(axis, Axis::Emulated { pos, neg }
+                    if *pos == button || *neg == button)
+            })
+    }
+}
+
+impl<ActionId, AxisId> Bindings<ActionId, AxisId>
+where
+    ActionId: Clone + Eq + Hash + Send + Sync + Serialize + DeserializeOwned,
+    AxisId: Clone + Eq + Hash + Send + Sync + Serialize + DeserializeOwned,
+{
+    /// Loads bindings written by save. See from_toml.
+    pub fn load(
+        path: impl AsRef<Path>,
+        defaults: &Self,
+    ) -> Result<(Self, Vec<BindingProblem<ActionId>>), InputError> {
+        Self::from_toml(&fs::read_to_string(path)?, defaults)
+    }
+
+    /// Saves the bindings as TOML with the actions and axes sorted by
+    /// name.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), InputError> {
+        let path = path.as_ref();
+
+        if let Some(dir) =
+            path.parent().filter(|dir| !dir.as_os_str().is_empty())
+        {
+            fs::create_dir_all(dir)?;
+        }
+
+        fs::write(path, self.to_toml()?)?;
+        Ok(())
+    }
+
+    /// Reads bindings from TOML. Entries that can not be read are skipped
+    /// and reported instead of failing the whole file, then anything
+    /// missing is filled in from defaults. Conflicts are reported too.
+    pub fn from_toml(
+        text: &str,
+        defaults: &Self,
+    ) -> Result<(Self, Vec<BindingProblem<ActionId>>), InputError> {
+        let table: toml::Table = toml::from_str(text)?;
+        let mut bindings = Self::new();
+        let mut problems = Vec::new();
+
+        if let Some(toml::Value::Table(actions)) = table.get("actions") {
+            for (name, value) in actions {
+                let Ok(id) =
+                    parse_value::<ActionId>(&toml::Value::String(name.clone()))
+                else {
+                    problems.push(BindingProblem::UnknownAction(name.clone()));
+                    continue;
+                };
+
+                // Combinations with a bad button are dropped along with it.
+                let mut combinations = Vec::new();
+                for combination in as_list(value) {
+                    let (buttons, bad) = parse_list::<Button>(combination);
+                    if bad.is_empty() {
+                        combinations.push(buttons);
+                    }
+
+                    for value in bad {
+                        problems.push(BindingProblem::UnknownButton {
+                            binding: name.clone(),
+                            button: value.to_string(),
+                        });
+                    }
+                }
+
+                // Only left for the defaults when nothing could be read.
+                if !combinations.is_empty() || is_empty_list(value) {
+                    bindings.set_action(id, combinations);
+                }
+            }
+        }
+
+        if let Some(toml::Value::Table(axes)) = table.get("axes") {
+            for (name, value) in axes {
+                let Ok(id) =
+                    parse_value::<AxisId>(&toml::Value::String(name.clone()))
+                else {
+                    problems.push(BindingProblem::UnknownAxis(name.clone()));
+                    continue;
+                };
+
+                let (axes, bad) = parse_list::<Axis>(value);
+                for value in bad {
+                    problems.push(BindingProblem::InvalidAxis {
+                        binding: name.clone(),
+                        axis: value.to_string(),
+                    });
+                }
+
+                if !axes.is_empty() || is_empty_list(value) {
+                    bindings.axes.insert(id, axes);
+                }
+            }
+        }
+
+        bindings.merge_defaults(defaults);
+        problems.extend(bindings.conflicts());
+        Ok((bindings, problems))
+    }
+
+    pub fn to_toml(&self) -> Result<String, InputError> {
+        let mut actions = toml::Table::new();
+        for (id, bindings) in &self.actions {
+            actions.insert(key_name(id)?, toml::Value::try_from(bindings)?);
+        }
+
+        let mut axes = toml::Table::new();
+        for (id, bindings) in &self.axes {
+            axes.insert(key_name(id)?, toml::Value::try_from(bindings)?);
+        }
+
+        let mut table = toml::Table::new();
+        table.insert("actions".to_owned(), toml::Value::Table(actions));
+        table.insert("axes".to_owned(), toml::Value::Table(axes));
+
+        Ok(toml::to_string_pretty(&table)?)
+    }
+}
+
+/// Something wrong with a set of Bindings. None of these stop them from
+/// loading.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BindingProblem<ActionId> {
+    /// Name in the file that is not one of the actions.
+    UnknownAction(String),
+    /// Name in the file that is not one of the axes.
+    UnknownAxis(String),
+    /// Button that could not be read, such as a misspelt key name.
+    UnknownButton { binding: String, button: String },
+    /// Axis that could not be read.
+    InvalidAxis { binding: String, axis: String },
+    /// The same button combination is bound to more than one action.
+    Conflict {
+        buttons: Vec<Button>,
+        actions: Vec<ActionId>,
+    },
+}
+
+impl<ActionId: fmt::Debug> fmt::Display for BindingProblem<ActionId> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BindingProblem::UnknownAction(name) => {
+                write!(f, "unknown action {}", name)
+            }
+            BindingProblem::UnknownAxis(name) => {
+                write!(f, "unknown axis {}", name)
+            }
+            BindingProblem::UnknownButton { binding, button } => {
+                write!(f, "unknown button {} bound to {}", button, binding)
+            }
+            BindingProblem::InvalidAxis { binding, axis } => {
+                write!(f, "invalid axis {} bound to {}", axis, binding)
+            }
+            BindingProblem::Conflict { buttons, actions } => {
+                write!(
+                    f,
+                    "{:?} is bound to more than one action: {:?}",
+                    buttons, actions
+                )
+            }
+        }
+    }
+}
+
+fn same_buttons(a: &[Button], b: &[Button]) -> bool {
+    a.len() == b.len()
+        && a.iter().all(|button| b.contains(button))
+        && b.iter().all(|button| a.contains(button))
+}
+
+fn parse_value<T: DeserializeOwned>(
+    value: &toml::Value,
+) -> Result<T, toml::Value> {
+    value.clone().try_into().map_err(|_| value.clone())
+}
+
+/// Entries of an array. Anything else is read as a list of itself.
+fn as_list(value: &toml::Value) -> &[toml::Value] {
+    match value {
+        toml::Value::Array(values) => values,
+        value => std::slice::from_ref(value),
+    }
+}
+
+/// Parses every entry of an array, returning the ones that could be read
+/// and the ones that could not.
+fn parse_list<T: DeserializeOwned>(
+    value: &toml::Value,
+) -> (Vec<T>, Vec<toml::Value>) {
+    let mut good = Vec::new();
+    let mut bad = Vec::new();
+
+    for value in as_list(value) {
+        match parse_value(value) {
+            Ok(item) => good.push(item),
+            Err(value) => bad.push(value),
+        }
+    }
+
+    (good, bad)
+}
+
+fn is_empty_list(value: &toml::Value) -> bool {
+    matches!(value, toml::Value::Array(values) if values.is_empty())
+}
+
+/// Name an id is written under, such as Quit for a plain enum variant.
+fn key_name<T: Serialize>(id: &T) -> Result<String, InputError> {
+    Ok(match toml::Value::try_from(id)? {
+        toml::Value::String(name) => name,
+        value => value.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use winit::event::VirtualKeyCode;
+
+    #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    enum Action {
+        Jump,
+        Fire,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    enum Look {
+        Sideways,
+    }
+
+    fn defaults() -> Bindings<Action, Look> {
+        let mut bindings = Bindings::new();
+        bindings.insert_action(Action::Jump, [VirtualKeyCode::Space.into()]);
+        bindings.insert_action(Action::Fire, [VirtualKeyCode::F.into()]);
+        bindings
+    }
+
+    #[test]
+    fn unknown_names_are_reported_and_skipped() {
+        let text = r#"
+            [actions]
+            Jump = [[{ Key = "J" }], [{ Key = "Jay" }]]
+            Dance = [[{ Key = "D" }]]
+        "#;
+        let (bindings, problems) =
+            Bindings::from_toml(text, &defaults()).unwrap();
+
+        assert_eq!(
+            bindings.action(&Action::Jump),
+            [vec![Button::Key(VirtualKeyCode::J)]]
+        );
+        assert_eq!(
+            problems,
+            [
+                BindingProblem::UnknownAction("Dance".to_owned()),
+                BindingProblem::UnknownButton {
+                    binding: "Jump".to_owned(),
+                    button: r#"{ Key = "Jay" }"#.to_owned(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_actions_fall_back_to_the_defaults() {
+        let text = r#"
+            [actions]
+            Jump = [[{ Key = "J" }]]
+        "#;
+        let (bindings, problems) =
+            Bindings::from_toml(text, &defaults()).unwrap();
+
+        assert!(problems.is_empty());
+        assert_eq!(
+            bindings.action(&Action::Fire),
+            defaults().action(&Action::Fire)
+        );
+
+        let mut unbound = Bindings::new();
+        unbound.set_action(Action::Fire, Vec::new());
+        unbound.merge_defaults(&defaults());
+        assert!(unbound.action(&Action::Fire).is_empty());
+        assert_eq!(
+            unbound.action(&Action::Jump),
+            defaults().action(&Action::Jump)
+        );
+    }
+
+    #[test]
+    fn one_key_on_two_actions_is_a_conflict() {
+        let mut bindings = defaults();
+        bindings.rebind_action(Action::Fire, VirtualKeyCode::Space.into());
+
+        assert_eq!(bindings.conflicts().len(), 1);
+        match &bindings.conflicts()[0] {
+            BindingProblem::Conflict { buttons, actions } => {
+                assert_eq!(buttons, &[Button::Key(VirtualKeyCode::Space)]);
+                assert_eq!(actions.len(), 2);
+                assert!(actions.contains(&Action::Jump));
+                assert!(actions.contains(&Action::Fire));
+            }
+            problem => panic!("expected a conflict, got {}", problem),
+        }
+    }
+
+    #[test]
+    fn saved_bindings_load_the_same() {
+        let path = std::env::temp_dir().join(format!(
+            "ascending_input_bindings_{}.toml",
+            std::process::id()
+        ));
+        let mut bindings = defaults();
+        bindings.rebind_action(
+            Action::Jump,
+            winit::event::MouseButton::Right.into(),
+        );
+        bindings.insert_action(
+            Action::Fire,
+            [Button::ScanCode(42), VirtualKeyCode::F.into()],
+        );
+        bindings.insert_axis(
+            Look::Sideways,
+            Axis::Emulated {
+                pos: VirtualKeyCode::D.into(),
+                neg: VirtualKeyCode::A.into(),
+            },
+        );
+
+        bindings.save(&path).unwrap();
+        let (loaded, problems) =
+            Bindings::load(&path, &Bindings::new()).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert!(problems.is_empty());
+        assert_eq!(loaded.actions, bindings.actions);
+        assert_eq!(loaded.axes, bindings.axes);
+    }
+}
@@ -1,3 +1,5 @@
+use super::gamepad::GamepadButton;
+use super::touch::Gesture;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
@@ -8,6 +10,10 @@ pub enum Button {
     ScanCode(u32),
     // A mouse button.
     Mouse(winit::event::MouseButton),
+    // A button on any connected gamepad.
+    Gamepad(GamepadButton),
+    // A gesture on the touch screen.
+    Gesture(Gesture),
 }
 
 impl From<winit::event::VirtualKeyCode> for Button {
@@ -21,3 +27,15 @@ impl From<winit::event::MouseButton> for Button {
         Button::Mouse(value)
     }
 }
+
+impl From<GamepadButton> for Button {
+    fn from(value: GamepadButton) -> Self {
+        Button::Gamepad(value)
+    }
+}
+
+impl From<Gesture> for Button {
+    fn from(value: Gesture) -> Self {
+        Button::Gesture(value)
+    }
+}
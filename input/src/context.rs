@@ -0,0 +1,93 @@
+use super::bindings::Bindings;
+use super::button::Button;
+use serde::{Deserialize, Serialize};
+use std::hash::Hash;
+
+/// Which buttons a context keeps from the contexts below it.
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Consume {
+    /// Everything is passed down.
+    Nothing,
+    /// Buttons bound in the context are kept, the rest are passed down.
+    Bound,
+    /// Every button and axis is kept, such as for a menu over the game.
+    All,
+}
+
+/// Named set of bindings pushed onto an InputHandler above its own. Only
+/// the actions of the contexts on the stack are read.
+#[derive(Clone, Debug)]
+pub struct InputContext<ActionId, AxisId>
+where
+    ActionId: Clone + Eq + Hash + Send + Sync,
+    AxisId: Clone + Eq + Hash + Send + Sync,
+{
+    name: String,
+    pub(super) bindings: Bindings<ActionId, AxisId>,
+    consume: Consume,
+    /// Keys are typed text, so no action of this context or the ones
+    /// below it is read.
+    text_entry: bool,
+}
+
+impl<ActionId, AxisId> InputContext<ActionId, AxisId>
+where
+    ActionId: Clone + Eq + Hash + Send + Sync,
+    AxisId: Clone + Eq + Hash + Send + Sync,
+{
+    /// Context that keeps the buttons it binds.
+    pub fn new(name: &str, bindings: Bindings<ActionId, AxisId>) -> Self {
+        Self {
+            name: name.to_string(),
+            bindings,
+            consume: Consume::Bound,
+            text_entry: false,
+        }
+    }
+
+    pub fn with_consume(mut self, consume: Consume) -> Self {
+        self.consume = consume;
+        self
+    }
+
+    /// Turns off every action while the context is on the stack, besides
+    /// the ones of contexts pushed above it. Keys are kept from the
+    /// contexts below.
+    pub fn with_text_entry(mut self) -> Self {
+        self.text_entry = true;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn bindings(&self) -> &Bindings<ActionId, AxisId> {
+        &self.bindings
+    }
+
+    pub fn bindings_mut(&mut self) -> &mut Bindings<ActionId, AxisId> {
+        &mut self.bindings
+    }
+
+    pub fn consume(&self) -> Consume {
+        self.consume
+    }
+
+    pub fn is_text_entry(&self) -> bool {
+        self.text_entry
+    }
+
+    /// Whether button is kept from the contexts below.
+    pub fn consumes(&self, button: Button) -> bool {
+        let typed = self.text_entry
+            && matches!(button, Button::Key(_) | Button::ScanCode(_));
+
+        typed
+            || match self.consume {
+                Consume::Nothing => false,
+                Consume::Bound => self.bindings.binds(button),
+                Consume::All => true,
+            }
+    }
+}
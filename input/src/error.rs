@@ -0,0 +1,14 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum InputError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    TomlDe(#[from] toml::de::Error),
+    #[error(transparent)]
+    TomlSer(#[from] toml::ser::Error),
+    #[cfg(feature = "gilrs")]
+    #[error("failed to open gamepads: {0}")]
+    Gamepad(String),
+}
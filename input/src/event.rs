@@ -0,0 +1,119 @@
+use super::gamepad::GamepadEvent;
+use super::text::ImeEvent;
+use serde::{Deserialize, Serialize};
+use winit::dpi::PhysicalPosition;
+use winit::event::{
+    DeviceEvent, ElementState, Event, KeyboardInput, ModifiersState,
+    MouseButton, MouseScrollDelta, Touch, TouchPhase, VirtualKeyCode,
+    WindowEvent,
+};
+use winit::window::WindowId;
+
+/// The part of a winit event the InputHandler reads. Unlike winit events
+/// these can be saved and fed back in without a window.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum InputEvent {
+    Key {
+        key: VirtualKeyCode,
+        scan_code: u32,
+        pressed: bool,
+    },
+    MouseButton {
+        button: MouseButton,
+        pressed: bool,
+    },
+    /// Cursor position in physical pixels.
+    CursorMoved {
+        x: f64,
+        y: f64,
+    },
+    FocusLost,
+    Modifiers(ModifiersState),
+    Character(char),
+    /// IME turned on or off, or composing and committing text.
+    Ime(ImeEvent),
+    /// Relative mouse motion from the device.
+    MouseMotion {
+        dx: f64,
+        dy: f64,
+    },
+    MouseWheel(MouseScrollDelta),
+    /// Finger id on the touch screen, at x, y in physical pixels.
+    Touch {
+        id: u64,
+        phase: TouchPhase,
+        x: f64,
+        y: f64,
+    },
+    /// Comes from a GamepadSource rather than the window.
+    Gamepad(GamepadEvent),
+}
+
+impl InputEvent {
+    /// Converts the events the InputHandler cares about. Window events
+    /// for other windows are left out.
+    pub fn from_winit(window_id: WindowId, event: &Event<()>) -> Option<Self> {
+        match event {
+            Event::WindowEvent {
+                event,
+                window_id: id,
+            } if *id == window_id => match event {
+                WindowEvent::KeyboardInput {
+                    input:
+                        KeyboardInput {
+                            state,
+                            virtual_keycode: Some(key),
+                            scancode,
+                            ..
+                        },
+                    ..
+                } => Some(InputEvent::Key {
+                    key: *key,
+                    scan_code: *scancode,
+                    pressed: *state == ElementState::Pressed,
+                }),
+                WindowEvent::MouseInput { state, button, .. } => {
+                    Some(InputEvent::MouseButton {
+                        button: *button,
+                        pressed: *state == ElementState::Pressed,
+                    })
+                }
+                WindowEvent::CursorMoved {
+                    position: PhysicalPosition { x, y },
+                    ..
+                } => Some(InputEvent::CursorMoved { x: *x, y: *y }),
+                WindowEvent::Focused(false) => Some(InputEvent::FocusLost),
+                WindowEvent::ModifiersChanged(modifiers) => {
+                    Some(InputEvent::Modifiers(*modifiers))
+                }
+                WindowEvent::ReceivedCharacter(character) => {
+                    Some(InputEvent::Character(*character))
+                }
+                WindowEvent::Ime(ime) => Some(InputEvent::Ime(ime.into())),
+                WindowEvent::MouseWheel { delta, .. } => {
+                    Some(InputEvent::MouseWheel(*delta))
+                }
+                WindowEvent::Touch(Touch {
+                    id,
+                    phase,
+                    location: PhysicalPosition { x, y },
+                    ..
+                }) => Some(InputEvent::Touch {
+                    id: *id,
+                    phase: *phase,
+                    x: *x,
+                    y: *y,
+                }),
+                _ => None,
+            },
+            Event::DeviceEvent {
+                event: DeviceEvent::MouseMotion { delta },
+                ..
+            } => Some(InputEvent::MouseMotion {
+                dx: delta.0,
+                dy: delta.1,
+            }),
+            _ => None,
+        }
+    }
+}
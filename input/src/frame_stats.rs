@@ -0,0 +1,161 @@
+use std::collections::VecDeque;
+
+/// Frame times kept unless set otherwise, a few seconds at 60 fps.
+pub const DEFAULT_STATS_FRAMES: usize = 240;
+
+/// Rolling window of the last frame times, in seconds.
+#[derive(Clone, Debug)]
+pub struct FrameStats {
+    samples: VecDeque<f32>,
+    frames: usize,
+}
+
+impl Default for FrameStats {
+    fn default() -> Self {
+        Self::new(DEFAULT_STATS_FRAMES)
+    }
+}
+
+impl FrameStats {
+    /// Keeps the last frames frame times, at least 1.
+    pub fn new(frames: usize) -> Self {
+        let frames = frames.max(1);
+
+        Self {
+            samples: VecDeque::with_capacity(frames),
+            frames,
+        }
+    }
+
+    pub fn push(&mut self, seconds: f32) {
+        if self.samples.len() == self.frames {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(seconds);
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Frame times kept, oldest first.
+    pub fn samples(&self) -> impl Iterator<Item = f32> + '_ {
+        self.samples.iter().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Mean frame time, 0.0 with nothing kept.
+    pub fn average(&self) -> f32 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+
+        self.samples.iter().sum::<f32>() / self.samples.len() as f32
+    }
+
+    /// Longest frame time.
+    pub fn max(&self) -> f32 {
+        self.samples.iter().copied().fold(0.0, f32::max)
+    }
+
+    /// Frame time that fraction of the frames were as fast as or faster
+    /// than, so 0.99 gives the 99th percentile.
+    pub fn percentile(&self, fraction: f32) -> f32 {
+        let sorted = self.sorted();
+        if sorted.is_empty() {
+            return 0.0;
+        }
+
+        let rank = (fraction.clamp(0.0, 1.0) * sorted.len() as f32).ceil();
+        sorted[(rank as usize).clamp(1, sorted.len()) - 1]
+    }
+
+    /// Frames a second going by the average frame time.
+    pub fn fps(&self) -> f32 {
+        per_second(self.average())
+    }
+
+    /// Frames a second over the slowest 1% of frames, which shows stutter
+    /// the average hides.
+    pub fn low_fps(&self) -> f32 {
+        let sorted = self.sorted();
+        let slowest = sorted.len().div_ceil(100);
+        if slowest == 0 {
+            return 0.0;
+        }
+
+        let total: f32 = sorted.iter().rev().take(slowest).sum();
+        per_second(total / slowest as f32)
+    }
+
+    fn sorted(&self) -> Vec<f32> {
+        let mut sorted: Vec<f32> = self.samples.iter().copied().collect();
+        sorted.sort_by(f32::total_cmp);
+        sorted
+    }
+}
+
+fn per_second(seconds: f32) -> f32 {
+    if seconds > 0.0 {
+        1.0 / seconds
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_latest_frames_are_kept() {
+        let mut stats = FrameStats::new(3);
+        for seconds in [1.0, 2.0, 3.0, 4.0, 5.0] {
+            stats.push(seconds);
+        }
+
+        assert_eq!(stats.samples().collect::<Vec<_>>(), [3.0, 4.0, 5.0]);
+        assert_eq!(stats.average(), 4.0);
+
+        let mut stats = FrameStats::new(0);
+        stats.push(1.0);
+        stats.push(2.0);
+        assert_eq!(stats.samples().collect::<Vec<_>>(), [2.0]);
+    }
+
+    #[test]
+    fn one_percent_low_and_max_come_from_the_slowest_frames() {
+        let mut stats = FrameStats::new(200);
+        for frame in 0..200 {
+            // Two slow frames among fast ones.
+            stats.push(if frame % 100 == 50 { 0.0625 } else { 0.015625 });
+        }
+
+        assert_eq!(stats.max(), 0.0625);
+        assert_eq!(stats.low_fps(), 16.0);
+        assert_eq!(stats.percentile(0.5), 0.015625);
+        assert_eq!(stats.percentile(1.0), 0.0625);
+        assert!(stats.fps() > 60.0 && stats.fps() < 64.0);
+    }
+
+    #[test]
+    fn an_empty_window_reads_zero() {
+        let mut stats = FrameStats::default();
+        stats.push(0.5);
+        stats.clear();
+
+        assert!(stats.is_empty());
+        assert_eq!(stats.average(), 0.0);
+        assert_eq!(stats.max(), 0.0);
+        assert_eq!(stats.percentile(0.99), 0.0);
+        assert_eq!(stats.fps(), 0.0);
+        assert_eq!(stats.low_fps(), 0.0);
+    }
+}
@@ -1,11 +1,101 @@
-use std::time::Instant;
+use super::frame_stats::FrameStats;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Fixed steps run at most in one frame. Time past that is dropped so a
+/// long stall does not leave the simulation forever catching up.
+const MAX_TICKS: u32 = 8;
+
+/// Where FrameTime reads the time from. Implemented by SystemClock for
+/// the real time and by ManualClock.
+pub trait Clock: Debug + Send + Sync {
+    /// Time since some fixed point, which only ever goes forward.
+    fn now(&self) -> Duration;
+
+    /// Waits for duration to go by.
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+}
+
+/// The real time.
+#[derive(Copy, Clone, Debug)]
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// Clock moved by hand, such as from a test. Clones share the same time
+/// so one can be given to FrameTime and another kept to advance it.
+#[derive(Clone, Debug, Default)]
+pub struct ManualClock {
+    nanos: Arc<AtomicU64>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn advance(&self, seconds: f32) {
+        let nanos = Duration::from_secs_f32(seconds.max(0.0)).as_nanos();
+        self.nanos.fetch_add(nanos as u64, Ordering::Relaxed);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        Duration::from_nanos(self.nanos.load(Ordering::Relaxed))
+    }
+
+    /// Moves the time on instead of waiting.
+    fn sleep(&self, duration: Duration) {
+        self.nanos
+            .fetch_add(duration.as_nanos() as u64, Ordering::Relaxed);
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct FrameTime {
+    clock: Arc<dyn Clock>,
+    /// Frames finished since the start.
+    frame: u64,
     delta_seconds: f32,
     seconds: f32,
-    frame_time: Instant,
-    start_time: Instant,
+    /// Clock reading at the start and at the last update.
+    start_time: Duration,
+    frame_time: Duration,
+    /// Speed the scaled time runs at, 1.0 being the real speed.
+    time_scale: f32,
+    paused: bool,
+    scaled_delta_seconds: f32,
+    scaled_seconds: f32,
+    /// Scaled seconds each fixed step covers.
+    fixed_step: f32,
+    /// Scaled time not yet used up by a fixed step.
+    accumulator: f32,
+    /// Fixed steps due this frame.
+    ticks: u32,
+    /// Frames a second pace holds to, None for no limit.
+    max_fps: Option<f32>,
+    /// Clock reading pace waits for before the next frame.
+    next_frame: Duration,
+    stats: FrameStats,
 }
 
 impl FrameTime {
@@ -15,26 +105,269 @@ impl FrameTime {
 
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
-        let instant = Instant::now();
+        Self::with_clock(SystemClock::default())
+    }
+
+    /// Times frames with clock instead of the real time.
+    pub fn with_clock(clock: impl Clock + 'static) -> Self {
+        let instant = clock.now();
 
         Self {
+            clock: Arc::new(clock),
+            frame: 0,
             delta_seconds: 0.0,
             seconds: 0.0,
-            frame_time: instant,
             start_time: instant,
+            frame_time: instant,
+            time_scale: 1.0,
+            paused: false,
+            scaled_delta_seconds: 0.0,
+            scaled_seconds: 0.0,
+            fixed_step: 1.0 / 60.0,
+            accumulator: 0.0,
+            ticks: 0,
+            max_fps: None,
+            next_frame: instant,
+            stats: FrameStats::default(),
         }
     }
 
+    /// Number of the frame being worked on, starting from 0.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
     pub fn seconds(&self) -> f32 {
         self.seconds
     }
 
+    /// Length of the last frame after the time scale, 0.0 while paused.
+    /// Use for what slows down and stops with the game, and the real
+    /// delta_seconds for everything else such as menus and input.
+    pub fn scaled_delta_seconds(&self) -> f32 {
+        self.scaled_delta_seconds
+    }
+
+    /// Scaled seconds since the start, which stop while paused.
+    pub fn scaled_seconds(&self) -> f32 {
+        self.scaled_seconds
+    }
+
+    pub fn time_scale(&self) -> f32 {
+        self.time_scale
+    }
+
+    /// 0.5 runs the scaled time at half speed. Negative scales count as
+    /// 0.0.
+    pub fn set_time_scale(&mut self, time_scale: f32) {
+        self.time_scale = time_scale.max(0.0);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Stops the scaled time and fixed steps until unpaused. The real
+    /// time keeps going.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    pub fn fixed_step(&self) -> f32 {
+        self.fixed_step
+    }
+
+    /// Scaled seconds each fixed step covers, 1.0 / 60.0 unless set.
+    pub fn set_fixed_step(&mut self, seconds: f32) {
+        self.fixed_step = seconds.max(f32::EPSILON);
+    }
+
+    /// Fixed steps to run this frame so the simulation keeps pace with the
+    /// scaled time. Can be 0 on fast frames.
+    pub fn ticks(&self) -> u32 {
+        self.ticks
+    }
+
+    /// How far into the next fixed step the scaled time is, from 0.0 to
+    /// 1.0. Used to draw between the last two steps so motion stays smooth
+    /// when frames and steps do not line up.
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator / self.fixed_step).clamp(0.0, 1.0)
+    }
+
+    pub fn max_fps(&self) -> Option<f32> {
+        self.max_fps
+    }
+
+    /// Frame rate pace holds to, which works with or without vsync. None
+    /// or a rate of 0.0 or less lifts the limit.
+    pub fn set_max_fps(&mut self, max_fps: Option<f32>) {
+        self.max_fps = max_fps.filter(|max_fps| *max_fps > 0.0);
+    }
+
+    /// Waits until the next frame is due under max_fps. Call right before
+    /// update. Frames that run late start the count again rather than
+    /// rushing the ones after to catch up.
+    pub fn pace(&mut self) {
+        let Some(max_fps) = self.max_fps else {
+            return;
+        };
+
+        let interval = Duration::from_secs_f32(1.0 / max_fps);
+        let now = self.clock.now();
+
+        if now < self.next_frame {
+            self.clock.sleep(self.next_frame - now);
+            self.next_frame += interval;
+        } else {
+            self.next_frame = now + interval;
+        }
+    }
+
+    /// Times of the last frames, for showing the frame rate and spotting
+    /// stutter.
+    pub fn stats(&self) -> &FrameStats {
+        &self.stats
+    }
+
+    pub fn stats_mut(&mut self) -> &mut FrameStats {
+        &mut self.stats
+    }
+
     pub fn update(&mut self) {
-        let frame_time = Instant::now();
+        let frame_time = self.clock.now();
 
         self.delta_seconds =
-            frame_time.duration_since(self.frame_time).as_secs_f32();
-        self.seconds = frame_time.duration_since(self.start_time).as_secs_f32();
+            frame_time.saturating_sub(self.frame_time).as_secs_f32();
+        self.seconds = frame_time.saturating_sub(self.start_time).as_secs_f32();
         self.frame_time = frame_time;
+        self.frame += 1;
+        self.stats.push(self.delta_seconds);
+
+        self.scaled_delta_seconds = if self.paused {
+            0.0
+        } else {
+            self.delta_seconds * self.time_scale
+        };
+        self.scaled_seconds += self.scaled_delta_seconds;
+
+        self.accumulator += self.scaled_delta_seconds;
+        let steps = (self.accumulator / self.fixed_step).floor();
+        self.accumulator -= steps * self.fixed_step;
+        self.ticks = (steps as u32).min(MAX_TICKS);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame_time() -> (ManualClock, FrameTime) {
+        let clock = ManualClock::new();
+        (clock.clone(), FrameTime::with_clock(clock))
+    }
+
+    #[test]
+    fn fixed_steps_keep_pace_with_time() {
+        let (clock, mut frame_time) = frame_time();
+        frame_time.set_fixed_step(0.25);
+
+        clock.advance(0.625);
+        frame_time.update();
+        assert_eq!(frame_time.ticks(), 2);
+        assert_eq!(frame_time.alpha(), 0.5);
+
+        clock.advance(0.125);
+        frame_time.update();
+        assert_eq!(frame_time.ticks(), 1);
+        assert_eq!(frame_time.alpha(), 0.0);
+
+        clock.advance(0.125);
+        frame_time.update();
+        assert_eq!(frame_time.ticks(), 0);
+        assert_eq!(frame_time.frame(), 3);
+    }
+
+    #[test]
+    fn stall_runs_at_most_max_ticks() {
+        let (clock, mut frame_time) = frame_time();
+        frame_time.set_fixed_step(0.25);
+
+        clock.advance(10.0);
+        frame_time.update();
+        assert_eq!(frame_time.ticks(), MAX_TICKS);
+
+        // The dropped time is not caught up on later.
+        clock.advance(0.125);
+        frame_time.update();
+        assert_eq!(frame_time.ticks(), 0);
+    }
+
+    #[test]
+    fn time_scale_slows_scaled_time() {
+        let (clock, mut frame_time) = frame_time();
+        frame_time.set_fixed_step(0.25);
+        frame_time.set_time_scale(0.5);
+
+        clock.advance(1.0);
+        frame_time.update();
+        assert_eq!(frame_time.delta_seconds(), 1.0);
+        assert_eq!(frame_time.seconds(), 1.0);
+        assert_eq!(frame_time.scaled_delta_seconds(), 0.5);
+        assert_eq!(frame_time.scaled_seconds(), 0.5);
+        assert_eq!(frame_time.ticks(), 2);
+
+        frame_time.set_time_scale(-1.0);
+        assert_eq!(frame_time.time_scale(), 0.0);
+    }
+
+    #[test]
+    fn pause_stops_scaled_time() {
+        let (clock, mut frame_time) = frame_time();
+        frame_time.set_fixed_step(0.25);
+        frame_time.set_paused(true);
+
+        clock.advance(1.0);
+        frame_time.update();
+        assert_eq!(frame_time.seconds(), 1.0);
+        assert_eq!(frame_time.scaled_delta_seconds(), 0.0);
+        assert_eq!(frame_time.scaled_seconds(), 0.0);
+        assert_eq!(frame_time.ticks(), 0);
+
+        frame_time.set_paused(false);
+        clock.advance(0.5);
+        frame_time.update();
+        assert_eq!(frame_time.scaled_seconds(), 0.5);
+        assert_eq!(frame_time.ticks(), 2);
+    }
+
+    #[test]
+    fn pace_waits_for_the_next_frame() {
+        let (clock, mut frame_time) = frame_time();
+        frame_time.set_max_fps(Some(4.0));
+
+        frame_time.pace();
+        frame_time.update();
+        assert_eq!(clock.now(), Duration::ZERO);
+
+        // An early frame waits out the rest of the interval.
+        clock.advance(0.125);
+        frame_time.pace();
+        frame_time.update();
+        assert_eq!(clock.now(), Duration::from_millis(250));
+        assert_eq!(frame_time.delta_seconds(), 0.25);
+
+        // A late frame goes right away and the count starts again from it.
+        clock.advance(1.0);
+        frame_time.pace();
+        assert_eq!(clock.now(), Duration::from_millis(1250));
+        clock.advance(0.125);
+        frame_time.pace();
+        assert_eq!(clock.now(), Duration::from_millis(1500));
+
+        frame_time.set_max_fps(None);
+        clock.advance(0.125);
+        frame_time.pace();
+        assert_eq!(clock.now(), Duration::from_millis(1625));
     }
 }
@@ -0,0 +1,284 @@
+#[cfg(feature = "gilrs")]
+mod gilrs_source;
+
+#[cfg(feature = "gilrs")]
+pub use gilrs_source::GilrsSource;
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Gamepads connected at once get their own id.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Hash,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Serialize,
+    Deserialize,
+)]
+pub struct GamepadId(pub usize);
+
+/// Buttons named by where they sit on the pad, so South is A on an Xbox
+/// pad and Cross on a PlayStation one.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub enum GamepadButton {
+    South,
+    East,
+    North,
+    West,
+    LeftBumper,
+    RightBumper,
+    /// Counts as pressed once pulled far enough. Read the GamepadAxis for
+    /// how far.
+    LeftTrigger,
+    RightTrigger,
+    Select,
+    Start,
+    Mode,
+    /// Pressing the stick in.
+    LeftStick,
+    RightStick,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+/// Sticks go from -1.0 to 1.0 with up and right positive. Triggers go
+/// from 0.0 to 1.0.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+    RightStickX,
+    RightStickY,
+    LeftTrigger,
+    RightTrigger,
+}
+
+/// How the value of a gamepad axis is shaped once past its dead zone.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ResponseCurve {
+    Linear,
+    /// Finer control near the center.
+    Quadratic,
+    /// Even finer control near the center.
+    Cubic,
+}
+
+impl ResponseCurve {
+    /// Values inside dead_zone become 0.0. The rest is scaled to start
+    /// from 0.0 at its edge before the curve is applied.
+    pub fn apply(&self, value: f32, dead_zone: f32) -> f32 {
+        let magnitude = value.abs().min(1.0);
+        if magnitude <= dead_zone {
+            return 0.0;
+        }
+
+        let scaled =
+            (magnitude - dead_zone) / (1.0 - dead_zone).max(f32::EPSILON);
+        let curved = match self {
+            ResponseCurve::Linear => scaled,
+            ResponseCurve::Quadratic => scaled * scaled,
+            ResponseCurve::Cubic => scaled * scaled * scaled,
+        };
+
+        curved.copysign(value)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum GamepadEvent {
+    Connected(GamepadId),
+    /// Everything the pad held is let go.
+    Disconnected(GamepadId),
+    Button {
+        id: GamepadId,
+        button: GamepadButton,
+        pressed: bool,
+    },
+    Axis {
+        id: GamepadId,
+        axis: GamepadAxis,
+        value: f32,
+    },
+}
+
+/// Where the InputHandler gets gamepad events from. Implemented by the
+/// backend reading real devices and by FakeGamepad.
+pub trait GamepadSource {
+    /// The next event that came in, or None once there are no more for
+    /// now.
+    fn next_event(&mut self) -> Option<GamepadEvent>;
+}
+
+/// Gamepad driven by hand, such as from a test.
+#[derive(Clone, Debug, Default)]
+pub struct FakeGamepad {
+    id: GamepadId,
+    events: VecDeque<GamepadEvent>,
+}
+
+impl FakeGamepad {
+    pub fn new(id: GamepadId) -> Self {
+        Self {
+            id,
+            events: VecDeque::new(),
+        }
+    }
+
+    pub fn id(&self) -> GamepadId {
+        self.id
+    }
+
+    pub fn connect(&mut self) {
+        self.events.push_back(GamepadEvent::Connected(self.id));
+    }
+
+    pub fn disconnect(&mut self) {
+        self.events.push_back(GamepadEvent::Disconnected(self.id));
+    }
+
+    pub fn press(&mut self, button: GamepadButton) {
+        self.set_button(button, true);
+    }
+
+    pub fn release(&mut self, button: GamepadButton) {
+        self.set_button(button, false);
+    }
+
+    pub fn set_button(&mut self, button: GamepadButton, pressed: bool) {
+        self.events.push_back(GamepadEvent::Button {
+            id: self.id,
+            button,
+            pressed,
+        });
+    }
+
+    pub fn set_axis(&mut self, axis: GamepadAxis, value: f32) {
+        self.events.push_back(GamepadEvent::Axis {
+            id: self.id,
+            axis,
+            value,
+        });
+    }
+}
+
+impl GamepadSource for FakeGamepad {
+    fn next_event(&mut self) -> Option<GamepadEvent> {
+        self.events.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Axis, Bindings, Button, InputHandler};
+    use ordered_float::NotNan;
+
+    #[derive(
+        Copy, Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize,
+    )]
+    enum Input {
+        Confirm,
+        MoveX,
+    }
+
+    fn handler() -> InputHandler<Input, Input> {
+        let mut bindings = Bindings::new();
+        bindings.insert_action(
+            Input::Confirm,
+            [Button::Gamepad(GamepadButton::South)],
+        );
+        bindings.insert_axis(
+            Input::MoveX,
+            Axis::Gamepad {
+                axis: GamepadAxis::LeftStickX,
+                dead_zone: NotNan::new(0.25).unwrap(),
+                curve: ResponseCurve::Linear,
+            },
+        );
+        InputHandler::new(bindings)
+    }
+
+    #[test]
+    fn buttons_drive_actions() {
+        let mut handler = handler();
+        let mut pad = FakeGamepad::new(GamepadId(1));
+        pad.connect();
+        pad.press(GamepadButton::South);
+
+        assert_eq!(handler.poll_gamepads(&mut pad).len(), 2);
+        assert_eq!(handler.gamepads().collect::<Vec<_>>(), [GamepadId(1)]);
+        assert!(handler.is_action_pressed(&Input::Confirm));
+        assert!(handler.is_action_down(&Input::Confirm));
+        assert!(handler.is_gamepad_button_pressed(GamepadButton::South));
+
+        handler.end_frame();
+        handler.set_time(1.0);
+        assert!(!handler.is_action_pressed(&Input::Confirm));
+        assert_eq!(handler.action_hold_duration(&Input::Confirm), 1.0);
+
+        pad.release(GamepadButton::South);
+        handler.poll_gamepads(&mut pad);
+        assert!(handler.is_action_released(&Input::Confirm));
+        assert!(!handler.is_action_down(&Input::Confirm));
+        assert!(!handler.is_gamepad_button_down(GamepadButton::South));
+        assert_eq!(
+            handler.hold_duration(Button::Gamepad(GamepadButton::South)),
+            0.0
+        );
+    }
+
+    #[test]
+    fn disconnecting_lets_go_of_buttons() {
+        let mut handler = handler();
+        let mut pad = FakeGamepad::new(GamepadId(0));
+        pad.connect();
+        pad.press(GamepadButton::South);
+        pad.set_axis(GamepadAxis::LeftStickX, 1.0);
+        handler.poll_gamepads(&mut pad);
+        handler.end_frame();
+
+        pad.disconnect();
+        handler.poll_gamepads(&mut pad);
+        assert_eq!(handler.gamepads().count(), 0);
+        assert!(handler.is_action_released(&Input::Confirm));
+        assert!(!handler.is_gamepad_button_down(GamepadButton::South));
+        assert_eq!(handler.axis_value(&Input::MoveX), 0.0);
+    }
+
+    #[test]
+    fn axes_skip_the_dead_zone() {
+        let mut handler = handler();
+        let mut pad = FakeGamepad::new(GamepadId(0));
+        pad.connect();
+
+        pad.set_axis(GamepadAxis::LeftStickX, 0.2);
+        handler.poll_gamepads(&mut pad);
+        assert_eq!(handler.gamepad_axis_value(GamepadAxis::LeftStickX), 0.2);
+        assert_eq!(handler.axis_value(&Input::MoveX), 0.0);
+
+        pad.set_axis(GamepadAxis::LeftStickX, 0.625);
+        handler.poll_gamepads(&mut pad);
+        assert_eq!(handler.axis_value(&Input::MoveX), 0.5);
+
+        pad.set_axis(GamepadAxis::LeftStickX, -1.0);
+        handler.poll_gamepads(&mut pad);
+        assert_eq!(handler.axis_value(&Input::MoveX), -1.0);
+    }
+
+    #[test]
+    fn response_curves_shape_past_the_dead_zone() {
+        assert_eq!(ResponseCurve::Linear.apply(0.1, 0.25), 0.0);
+        assert_eq!(ResponseCurve::Linear.apply(-0.625, 0.25), -0.5);
+        assert_eq!(ResponseCurve::Quadratic.apply(0.625, 0.25), 0.25);
+        assert_eq!(ResponseCurve::Cubic.apply(-0.625, 0.25), -0.125);
+        assert_eq!(ResponseCurve::Linear.apply(2.0, 0.0), 1.0);
+    }
+}
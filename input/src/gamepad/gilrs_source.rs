@@ -0,0 +1,106 @@
+use super::{
+    GamepadAxis, GamepadButton, GamepadEvent, GamepadId, GamepadSource,
+};
+use crate::error::InputError;
+use gilrs::{Axis, Button, EventType, Gilrs};
+
+/// Reads the gamepads plugged into the machine through gilrs.
+pub struct GilrsSource {
+    gilrs: Gilrs,
+}
+
+impl GilrsSource {
+    /// Platforms gilrs does not support still work, they just never see a
+    /// gamepad.
+    pub fn new() -> Result<Self, InputError> {
+        let gilrs = match Gilrs::new() {
+            Ok(gilrs) => gilrs,
+            Err(gilrs::Error::NotImplemented(gilrs)) => gilrs,
+            Err(e) => return Err(InputError::Gamepad(e.to_string())),
+        };
+
+        Ok(Self { gilrs })
+    }
+}
+
+impl GamepadSource for GilrsSource {
+    fn next_event(&mut self) -> Option<GamepadEvent> {
+        // Skip the events with nothing to map to.
+        while let Some(event) = self.gilrs.next_event() {
+            let id = GamepadId(event.id.into());
+
+            let mapped = match event.event {
+                EventType::Connected => Some(GamepadEvent::Connected(id)),
+                EventType::Disconnected => Some(GamepadEvent::Disconnected(id)),
+                EventType::ButtonPressed(button, _) => {
+                    map_button(button).map(|button| GamepadEvent::Button {
+                        id,
+                        button,
+                        pressed: true,
+                    })
+                }
+                EventType::ButtonReleased(button, _) => {
+                    map_button(button).map(|button| GamepadEvent::Button {
+                        id,
+                        button,
+                        pressed: false,
+                    })
+                }
+                // Triggers are sent as buttons with how far they are pulled.
+                EventType::ButtonChanged(button, value, _) => {
+                    let axis = match button {
+                        Button::LeftTrigger2 => Some(GamepadAxis::LeftTrigger),
+                        Button::RightTrigger2 => {
+                            Some(GamepadAxis::RightTrigger)
+                        }
+                        _ => None,
+                    };
+
+                    axis.map(|axis| GamepadEvent::Axis { id, axis, value })
+                }
+                EventType::AxisChanged(axis, value, _) => map_axis(axis)
+                    .map(|axis| GamepadEvent::Axis { id, axis, value }),
+                _ => None,
+            };
+
+            if mapped.is_some() {
+                return mapped;
+            }
+        }
+
+        None
+    }
+}
+
+fn map_button(button: Button) -> Option<GamepadButton> {
+    Some(match button {
+        Button::South => GamepadButton::South,
+        Button::East => GamepadButton::East,
+        Button::North => GamepadButton::North,
+        Button::West => GamepadButton::West,
+        Button::LeftTrigger => GamepadButton::LeftBumper,
+        Button::RightTrigger => GamepadButton::RightBumper,
+        Button::LeftTrigger2 => GamepadButton::LeftTrigger,
+        Button::RightTrigger2 => GamepadButton::RightTrigger,
+        Button::Select => GamepadButton::Select,
+        Button::Start => GamepadButton::Start,
+        Button::Mode => GamepadButton::Mode,
+        Button::LeftThumb => GamepadButton::LeftStick,
+        Button::RightThumb => GamepadButton::RightStick,
+        Button::DPadUp => GamepadButton::DPadUp,
+        Button::DPadDown => GamepadButton::DPadDown,
+        Button::DPadLeft => GamepadButton::DPadLeft,
+        Button::DPadRight => GamepadButton::DPadRight,
+        _ => return None,
+    })
+}
+
+fn map_axis(axis: Axis) -> Option<GamepadAxis> {
+    Some(match axis {
+        Axis::LeftStickX => GamepadAxis::LeftStickX,
+        Axis::LeftStickY => GamepadAxis::LeftStickY,
+        Axis::RightStickX => GamepadAxis::RightStickX,
+        Axis::RightStickY => GamepadAxis::RightStickY,
+        _ => return None,
+    })
+}
@@ -1,13 +1,18 @@
-use super::axis::{Axis, MouseAxis};
+use super::axis::{Axis, MouseAxis, WheelSettings};
 use super::bindings::Bindings;
 use super::button::Button;
-use std::collections::HashSet;
+use super::context::{Consume, InputContext};
+use super::event::InputEvent;
+use super::gamepad::{
+    GamepadAxis, GamepadButton, GamepadEvent, GamepadId, GamepadSource,
+};
+use super::text::{Composition, ImeEvent, TextInput};
+use super::timing::{Held, TimingSettings};
+use super::touch::{Gesture, GestureSettings, TouchPoint, Touches};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::hash::Hash;
 use winit::dpi::PhysicalPosition;
-use winit::event::{
-    DeviceEvent, ElementState, Event, KeyboardInput, ModifiersState,
-    MouseScrollDelta, WindowEvent,
-};
+use winit::event::{Event, ModifiersState, MouseButton, MouseScrollDelta};
 use winit::window::Window;
 
 pub struct InputHandler<ActionId, AxisId>
@@ -17,12 +22,50 @@ where
 {
     /// The bindings.
     bindings: Bindings<ActionId, AxisId>,
+    /// Contexts pushed above the bindings, the last one on top.
+    contexts: Vec<InputContext<ActionId, AxisId>>,
     /// The set of keys that are currently pressed down by their virtual key code.
     keys: HashSet<winit::event::VirtualKeyCode>,
     /// The set of keys that are currently pressed down by their scan code.
     scan_codes: HashSet<u32>,
     /// The set of mouse buttons that are currently pressed down.
     mouse_buttons: HashSet<winit::event::MouseButton>,
+    /// Keys that went down since the last end_frame. OS repeats are left out.
+    pressed_keys: HashSet<winit::event::VirtualKeyCode>,
+    /// Keys that went up since the last end_frame.
+    released_keys: HashSet<winit::event::VirtualKeyCode>,
+    /// Scan codes that went down since the last end_frame.
+    pressed_scan_codes: HashSet<u32>,
+    /// Scan codes that went up since the last end_frame.
+    released_scan_codes: HashSet<u32>,
+    /// Mouse buttons that went down since the last end_frame.
+    pressed_mouse_buttons: HashSet<winit::event::MouseButton>,
+    /// Mouse buttons that went up since the last end_frame.
+    released_mouse_buttons: HashSet<winit::event::MouseButton>,
+    /// Gamepads that are connected.
+    gamepads: BTreeSet<GamepadId>,
+    /// The gamepad buttons that are currently pressed down on each pad.
+    gamepad_buttons: HashSet<(GamepadId, GamepadButton)>,
+    /// Gamepad buttons that went down on any pad since the last end_frame.
+    pressed_gamepad_buttons: HashSet<GamepadButton>,
+    /// Gamepad buttons that went up on any pad since the last end_frame.
+    released_gamepad_buttons: HashSet<GamepadButton>,
+    /// The last value of each gamepad axis on each pad.
+    gamepad_axes: HashMap<(GamepadId, GamepadAxis), f32>,
+    /// Fingers on the touch screen and their gestures.
+    touches: Touches,
+    /// Seconds since the start as last set.
+    seconds: f32,
+    timing: TimingSettings,
+    /// Every button held down and when it went down.
+    held: HashMap<Button, Held>,
+    /// Held buttons that repeated since the last end_frame.
+    repeated: HashSet<Button>,
+    /// Button, time and place of the last click, for spotting a double
+    /// click.
+    last_click: Option<(MouseButton, f32, (f32, f32))>,
+    /// Mouse buttons double clicked since the last end_frame.
+    double_clicks: HashSet<MouseButton>,
     /// The current mouse position.
     physical_mouse_position: Option<PhysicalPosition<f64>>,
     /// The current mouse position.
@@ -31,10 +74,31 @@ where
     last_mouse_position: Option<(f32, f32)>,
     /// The mouse delta, i.e. the relative mouse motion.
     mouse_delta: (f64, f64),
-    /// The current state of the mouse wheel.
-    mouse_wheel: (f32, f32),
+    /// Lines the mouse wheel moved since the last end_frame.
+    wheel_lines: (f32, f32),
+    /// Pixels touchpads scrolled since the last end_frame.
+    wheel_pixels: (f64, f64),
+    wheel: WheelSettings,
     //key modifiers.
     modifiers: ModifiersState,
+    /// Characters typed and text committed by the IME this frame in the
+    /// order they came in.
+    characters: String,
+    /// Typed characters and IME events this frame in the order they came
+    /// in.
+    text_input: Vec<TextInput>,
+    /// What the IME is composing, kept across frames until it commits or
+    /// cancels.
+    composition: Option<Composition>,
+    ime_enabled: bool,
+    /// Keys pressed this frame in order, including repeats sent by the OS.
+    key_presses: Vec<winit::event::VirtualKeyCode>,
+    /// Action waiting for the next pressed button to be bound to it.
+    rebinding: Option<ActionId>,
+    /// Action and button of the last finished rebind until it is taken.
+    rebound: Option<(ActionId, Button)>,
+    /// Buttons a rebind took, left out of the input until they are let go.
+    captured: HashSet<Button>,
 }
 
 impl<ActionId, AxisId> InputHandler<ActionId, AxisId>
@@ -47,12 +111,8 @@ where
         AxisId: std::borrow::Borrow<A>,
         A: Hash + Eq + ?Sized,
     {
-        let axes = match self.bindings.axes.get(id) {
-            Some(axes) => axes,
-            _ => return 0.0,
-        };
-
-        axes.iter()
+        self.axes(id)
+            .into_iter()
             .map(|axis| self.map_axis_value(axis))
             .max_by(|x, y| x.abs().partial_cmp(&y.abs()).unwrap())
             .unwrap_or(0.0)
@@ -61,7 +121,71 @@ where
     pub fn end_frame(&mut self) {
         self.last_mouse_position = self.mouse_position;
         self.mouse_delta = (0.0, 0.0);
-        self.mouse_wheel = (0.0, 0.0);
+        self.wheel_lines = (0.0, 0.0);
+        self.wheel_pixels = (0.0, 0.0);
+        self.characters.clear();
+        self.text_input.clear();
+        self.key_presses.clear();
+        self.pressed_keys.clear();
+        self.released_keys.clear();
+        self.pressed_scan_codes.clear();
+        self.released_scan_codes.clear();
+        self.pressed_mouse_buttons.clear();
+        self.released_mouse_buttons.clear();
+        self.pressed_gamepad_buttons.clear();
+        self.released_gamepad_buttons.clear();
+        self.touches.end_frame();
+        self.repeated.clear();
+        self.double_clicks.clear();
+    }
+
+    /// Seconds since the start, which holds, repeats, double clicks and
+    /// touches are timed with. Call once a frame with FrameTime::seconds
+    /// before input is read.
+    pub fn set_time(&mut self, seconds: f32) {
+        self.seconds = seconds;
+        self.touches.update_time(seconds);
+        self.track_gestures();
+
+        for (button, held) in &mut self.held {
+            if held.repeat(seconds, &self.timing) {
+                self.repeated.insert(*button);
+            }
+        }
+    }
+
+    pub fn seconds(&self) -> f32 {
+        self.seconds
+    }
+
+    /// Characters typed and text committed by the IME since the last
+    /// end_frame. Includes control characters such as backspace which text
+    /// fields should skip.
+    pub fn characters(&self) -> &str {
+        &self.characters
+    }
+
+    /// Characters typed and IME events since the last end_frame in the
+    /// order they came in, for text fields that show the composition.
+    pub fn text_input(&self) -> &[TextInput] {
+        &self.text_input
+    }
+
+    /// What the IME is composing right now, if anything.
+    pub fn composition(&self) -> Option<&Composition> {
+        self.composition.as_ref()
+    }
+
+    /// Whether the IME is on. It only turns on while the window allows it
+    /// with Window::set_ime_allowed.
+    pub fn is_ime_enabled(&self) -> bool {
+        self.ime_enabled
+    }
+
+    /// Keys pressed since the last end_frame in the order they were
+    /// pressed. Held keys show up again each time the OS repeats them.
+    pub fn key_presses(&self) -> &[winit::event::VirtualKeyCode] {
+        &self.key_presses
     }
 
     /// Looks up the set of bindings for the action, and then checks if there is any binding for
@@ -71,15 +195,317 @@ where
         ActionId: std::borrow::Borrow<A>,
         A: Hash + Eq + ?Sized,
     {
-        self.bindings
-            .actions
-            .get(action)
-            .map(|bindings| {
-                bindings.iter().any(|buttons| {
-                    buttons.iter().all(|button| self.is_button_down(*button))
-                })
+        self.combinations(action).iter().any(|buttons| {
+            buttons.iter().all(|button| self.is_button_down(*button))
+        })
+    }
+
+    /// True on the frame any binding of the action becomes fully held
+    /// while none were before. A binding pressed and let go within the same
+    /// frame still counts.
+    pub fn is_action_pressed<A>(&self, action: &A) -> bool
+    where
+        ActionId: std::borrow::Borrow<A>,
+        A: Hash + Eq + ?Sized,
+    {
+        let bindings = self.combinations(action);
+
+        let was_down = bindings.iter().any(|buttons| {
+            buttons.iter().all(|button| self.was_button_down(*button))
+        });
+
+        !was_down
+            && bindings.iter().any(|buttons| {
+                buttons.iter().all(|button| {
+                    self.is_button_down(*button)
+                        || self.is_button_pressed(*button)
+                }) && buttons
+                    .iter()
+                    .any(|button| self.is_button_pressed(*button))
             })
-            .unwrap_or(false)
+    }
+
+    /// True on the frame the action stops being held, including bindings
+    /// pressed and let go within the same frame.
+    pub fn is_action_released<A>(&self, action: &A) -> bool
+    where
+        ActionId: std::borrow::Borrow<A>,
+        A: Hash + Eq + ?Sized,
+    {
+        let bindings = self.combinations(action);
+
+        !self.is_action_down(action)
+            && bindings.iter().any(|buttons| {
+                buttons.iter().all(|button| {
+                    self.was_button_down(*button)
+                        || self.is_button_pressed(*button)
+                }) && buttons
+                    .iter()
+                    .any(|button| self.is_button_released(*button))
+            })
+    }
+
+    /// Button combinations bound to the action that no context above the
+    /// one they are bound in consumes, top context first.
+    fn combinations<A>(&self, action: &A) -> Vec<&[Button]>
+    where
+        ActionId: std::borrow::Borrow<A>,
+        A: Hash + Eq + ?Sized,
+    {
+        let mut combinations = Vec::new();
+
+        for level in (0..=self.contexts.len()).rev() {
+            if level > 0 && self.contexts[level - 1].is_text_entry() {
+                break;
+            }
+
+            let above = &self.contexts[level..];
+            if let Some(bindings) = self.layer(level).actions.get(action) {
+                combinations.extend(
+                    bindings
+                        .iter()
+                        .filter(|buttons| {
+                            buttons.iter().all(|button| reaches(above, *button))
+                        })
+                        .map(Vec::as_slice),
+                );
+            }
+        }
+
+        combinations
+    }
+
+    /// Axes bound to id that no context above the one they are bound in
+    /// consumes, top context first.
+    fn axes<A>(&self, id: &A) -> Vec<&Axis>
+    where
+        AxisId: std::borrow::Borrow<A>,
+        A: Hash + Eq + ?Sized,
+    {
+        let mut axes = Vec::new();
+
+        for level in (0..=self.contexts.len()).rev() {
+            let above = &self.contexts[level..];
+            if let Some(bound) = self.layer(level).axes.get(id) {
+                axes.extend(bound.iter().filter(|axis| {
+                    match axis {
+                        Axis::Emulated { pos, neg } => {
+                            reaches(above, *pos) && reaches(above, *neg)
+                        }
+                        _ => above
+                            .iter()
+                            .all(|context| context.consume() != Consume::All),
+                    }
+                }));
+            }
+        }
+
+        axes
+    }
+
+    /// The bindings at level of the stack. The handlers own are level 0.
+    fn layer(&self, level: usize) -> &Bindings<ActionId, AxisId> {
+        match level.checked_sub(1) {
+            Some(index) => &self.contexts[index].bindings,
+            None => &self.bindings,
+        }
+    }
+
+    /// Pushes context on top of the stack. Its actions are read along with
+    /// the ones below, which lose the buttons it consumes.
+    pub fn push_context(&mut self, context: InputContext<ActionId, AxisId>) {
+        self.contexts.push(context);
+    }
+
+    pub fn pop_context(&mut self) -> Option<InputContext<ActionId, AxisId>> {
+        self.contexts.pop()
+    }
+
+    /// Takes the topmost context named name off the stack.
+    pub fn remove_context(
+        &mut self,
+        name: &str,
+    ) -> Option<InputContext<ActionId, AxisId>> {
+        let index = self
+            .contexts
+            .iter()
+            .rposition(|context| context.name() == name)?;
+
+        Some(self.contexts.remove(index))
+    }
+
+    pub fn has_context(&self, name: &str) -> bool {
+        self.contexts.iter().any(|context| context.name() == name)
+    }
+
+    pub fn context_mut(
+        &mut self,
+        name: &str,
+    ) -> Option<&mut InputContext<ActionId, AxisId>> {
+        self.contexts
+            .iter_mut()
+            .rev()
+            .find(|context| context.name() == name)
+    }
+
+    /// Contexts on the stack from the bottom up.
+    pub fn contexts(&self) -> &[InputContext<ActionId, AxisId>] {
+        &self.contexts
+    }
+
+    /// The bindings below every context.
+    pub fn bindings(&self) -> &Bindings<ActionId, AxisId> {
+        &self.bindings
+    }
+
+    pub fn set_bindings(&mut self, bindings: Bindings<ActionId, AxisId>) {
+        self.bindings = bindings;
+    }
+
+    /// Binds the action to the next button pressed, replacing what it was
+    /// bound to. That press does not count as pressing anything else.
+    pub fn start_rebind(&mut self, action: ActionId) {
+        self.rebinding = Some(action);
+    }
+
+    pub fn cancel_rebind(&mut self) {
+        self.rebinding = None;
+    }
+
+    /// Action waiting for a button, if any.
+    pub fn rebinding(&self) -> Option<&ActionId> {
+        self.rebinding.as_ref()
+    }
+
+    /// Action and button of the last finished rebind. Returns it once so
+    /// it can be saved.
+    pub fn take_rebind(&mut self) -> Option<(ActionId, Button)> {
+        self.rebound.take()
+    }
+
+    /// Finishes a rebind with button if one is waiting.
+    fn capture(&mut self, button: Button) -> bool {
+        match self.rebinding.take() {
+            Some(action) => {
+                self.bindings.rebind_action(action.clone(), button);
+                self.rebound = Some((action, button));
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn timing_settings(&self) -> &TimingSettings {
+        &self.timing
+    }
+
+    pub fn set_timing_settings(&mut self, settings: TimingSettings) {
+        self.timing = settings;
+    }
+
+    /// Seconds the button has been held for, or 0.0 if it is up.
+    pub fn hold_duration(&self, button: Button) -> f32 {
+        self.held
+            .get(&button)
+            .map_or(0.0, |held| self.seconds - held.since)
+    }
+
+    /// Seconds the longest held binding of the action has been fully held
+    /// for, or 0.0 if none are.
+    pub fn action_hold_duration<A>(&self, action: &A) -> f32
+    where
+        ActionId: std::borrow::Borrow<A>,
+        A: Hash + Eq + ?Sized,
+    {
+        self.combinations(action)
+            .iter()
+            .filter_map(|buttons| self.combination_held(buttons))
+            .map(|(held, _)| self.seconds - held.since)
+            .fold(0.0, f32::max)
+    }
+
+    /// True on the frames a held button repeats, starting once it has been
+    /// held for the repeat delay.
+    pub fn is_button_repeated(&self, button: Button) -> bool {
+        self.repeated.contains(&button)
+    }
+
+    /// True on the frames a fully held binding of the action repeats. A
+    /// binding repeats along with the last of its buttons pressed.
+    pub fn is_action_repeated<A>(&self, action: &A) -> bool
+    where
+        ActionId: std::borrow::Borrow<A>,
+        A: Hash + Eq + ?Sized,
+    {
+        self.combinations(action).iter().any(|buttons| {
+            self.combination_held(buttons)
+                .is_some_and(|(_, last)| self.is_button_repeated(last))
+        })
+    }
+
+    /// The Held of the last pressed button of a fully held combination,
+    /// along with that button.
+    fn combination_held(&self, buttons: &[Button]) -> Option<(Held, Button)> {
+        let held = buttons
+            .iter()
+            .map(|button| self.held.get(button).map(|held| (*held, *button)))
+            .collect::<Option<Vec<_>>>()?;
+
+        held.into_iter()
+            .max_by(|(x, _), (y, _)| x.since.total_cmp(&y.since))
+    }
+
+    /// True on the frame the second click of a double click went down.
+    pub fn is_double_click(&self, button: MouseButton) -> bool {
+        self.double_clicks.contains(&button)
+    }
+
+    /// Starts or stops timing button to match whether it is down.
+    fn track(&mut self, button: Button) {
+        if !self.is_button_down(button) {
+            self.held.remove(&button);
+        } else if !self.held.contains_key(&button) {
+            self.held
+                .insert(button, Held::new(self.seconds, &self.timing));
+        }
+    }
+
+    fn track_gestures(&mut self) {
+        for gesture in Gesture::ALL {
+            self.track(Button::Gesture(gesture));
+        }
+    }
+
+    /// Stops timing every button that is no longer down.
+    fn forget_released(&mut self) {
+        let released: Vec<_> = self
+            .held
+            .keys()
+            .filter(|button| !self.is_button_down(**button))
+            .copied()
+            .collect();
+
+        for button in released {
+            self.held.remove(&button);
+        }
+    }
+
+    /// Counts a click as the second of a double click if it is close
+    /// enough in time and place to the last one.
+    fn click(&mut self, button: MouseButton) {
+        let position = self.mouse_position.unwrap_or((0.0, 0.0));
+
+        match self.last_click.take() {
+            Some((last, time, place))
+                if last == button
+                    && self.seconds - time <= self.timing.double_click_time
+                    && (place.0 - position.0).hypot(place.1 - position.1)
+                        <= self.timing.double_click_distance =>
+            {
+                self.double_clicks.insert(button);
+            }
+            _ => self.last_click = Some((button, self.seconds, position)),
+        }
     }
 
     pub fn is_button_down(&self, button: Button) -> bool {
@@ -87,13 +513,53 @@ where
             Button::Key(key) => self.is_key_down(key),
             Button::ScanCode(scan_code) => self.is_scan_code_down(scan_code),
             Button::Mouse(button) => self.is_mouse_button_down(button),
+            Button::Gamepad(button) => self.is_gamepad_button_down(button),
+            Button::Gesture(gesture) => self.touches.is_down(gesture),
+        }
+    }
+
+    /// True if the button went down since the last end_frame.
+    pub fn is_button_pressed(&self, button: Button) -> bool {
+        match button {
+            Button::Key(key) => self.is_key_pressed(key),
+            Button::ScanCode(scan_code) => self.is_scan_code_pressed(scan_code),
+            Button::Mouse(button) => self.is_mouse_button_pressed(button),
+            Button::Gamepad(button) => self.is_gamepad_button_pressed(button),
+            Button::Gesture(gesture) => self.touches.is_pressed(gesture),
+        }
+    }
+
+    /// True if the button went up since the last end_frame.
+    pub fn is_button_released(&self, button: Button) -> bool {
+        match button {
+            Button::Key(key) => self.is_key_released(key),
+            Button::ScanCode(scan_code) => {
+                self.is_scan_code_released(scan_code)
+            }
+            Button::Mouse(button) => self.is_mouse_button_released(button),
+            Button::Gamepad(button) => self.is_gamepad_button_released(button),
+            Button::Gesture(gesture) => self.touches.is_released(gesture),
         }
     }
 
+    /// Whether the button was down at the end of the last frame.
+    fn was_button_down(&self, button: Button) -> bool {
+        (self.is_button_down(button) || self.is_button_released(button))
+            && !self.is_button_pressed(button)
+    }
+
     pub fn is_key_down(&self, key: winit::event::VirtualKeyCode) -> bool {
         self.keys.contains(&key)
     }
 
+    pub fn is_key_pressed(&self, key: winit::event::VirtualKeyCode) -> bool {
+        self.pressed_keys.contains(&key)
+    }
+
+    pub fn is_key_released(&self, key: winit::event::VirtualKeyCode) -> bool {
+        self.released_keys.contains(&key)
+    }
+
     pub fn is_mouse_button_down(
         &self,
         button: winit::event::MouseButton,
@@ -101,10 +567,121 @@ where
         self.mouse_buttons.contains(&button)
     }
 
+    pub fn is_mouse_button_pressed(
+        &self,
+        button: winit::event::MouseButton,
+    ) -> bool {
+        self.pressed_mouse_buttons.contains(&button)
+    }
+
+    pub fn is_mouse_button_released(
+        &self,
+        button: winit::event::MouseButton,
+    ) -> bool {
+        self.released_mouse_buttons.contains(&button)
+    }
+
     pub fn is_scan_code_down(&self, scan_code: u32) -> bool {
         self.scan_codes.contains(&scan_code)
     }
 
+    pub fn is_scan_code_pressed(&self, scan_code: u32) -> bool {
+        self.pressed_scan_codes.contains(&scan_code)
+    }
+
+    pub fn is_scan_code_released(&self, scan_code: u32) -> bool {
+        self.released_scan_codes.contains(&scan_code)
+    }
+
+    /// Fingers on the touch screen in the order they went down.
+    pub fn touches(&self) -> &[TouchPoint] {
+        self.touches.points()
+    }
+
+    pub fn is_gesture_down(&self, gesture: Gesture) -> bool {
+        self.touches.is_down(gesture)
+    }
+
+    pub fn is_gesture_pressed(&self, gesture: Gesture) -> bool {
+        self.touches.is_pressed(gesture)
+    }
+
+    pub fn is_gesture_released(&self, gesture: Gesture) -> bool {
+        self.touches.is_released(gesture)
+    }
+
+    /// Drag motion since the last end_frame, last position minus current.
+    pub fn touch_drag(&self) -> (f32, f32) {
+        self.touches.drag()
+    }
+
+    /// Change in the distance between two fingers since the last
+    /// end_frame.
+    pub fn pinch_delta(&self) -> f32 {
+        self.touches.pinch()
+    }
+
+    pub fn gesture_settings(&self) -> &GestureSettings {
+        &self.touches.settings
+    }
+
+    pub fn set_gesture_settings(&mut self, settings: GestureSettings) {
+        self.touches.settings = settings;
+    }
+
+    /// True while the button is held on any gamepad.
+    pub fn is_gamepad_button_down(&self, button: GamepadButton) -> bool {
+        self.gamepad_buttons.iter().any(|(_, held)| *held == button)
+    }
+
+    pub fn is_gamepad_button_pressed(&self, button: GamepadButton) -> bool {
+        self.pressed_gamepad_buttons.contains(&button)
+    }
+
+    pub fn is_gamepad_button_released(&self, button: GamepadButton) -> bool {
+        self.released_gamepad_buttons.contains(&button)
+    }
+
+    /// Raw value of the axis on the gamepad pushing it the furthest.
+    pub fn gamepad_axis_value(&self, axis: GamepadAxis) -> f32 {
+        self.gamepad_axes
+            .iter()
+            .filter(|((_, other), _)| *other == axis)
+            .map(|(_, value)| *value)
+            .max_by(|x, y| x.abs().partial_cmp(&y.abs()).unwrap())
+            .unwrap_or(0.0)
+    }
+
+    /// Raw value of the axis on one gamepad.
+    pub fn gamepad_axis_value_on(
+        &self,
+        id: GamepadId,
+        axis: GamepadAxis,
+    ) -> f32 {
+        self.gamepad_axes.get(&(id, axis)).copied().unwrap_or(0.0)
+    }
+
+    /// Connected gamepads by id.
+    pub fn gamepads(&self) -> impl Iterator<Item = GamepadId> + '_ {
+        self.gamepads.iter().copied()
+    }
+
+    /// Feeds in every event waiting in source. They are returned so they
+    /// can be recorded.
+    pub fn poll_gamepads<S>(&mut self, source: &mut S) -> Vec<GamepadEvent>
+    where
+        S: GamepadSource + ?Sized,
+    {
+        let mut events = Vec::new();
+
+        while let Some(event) = source.next_event() {
+            self.apply(&InputEvent::Gamepad(event), 1.0);
+            events.push(event);
+        }
+
+        events
+    }
+
     fn map_axis_value(&self, axis: &Axis) -> f32 {
         match axis {
             Axis::Emulated { pos, neg, .. } => {
@@ -157,6 +734,32 @@ where
                 }
             }
             Axis::MouseWheel { axis } => self.mouse_wheel_value(*axis),
+            Axis::TouchDrag {
+                axis,
+                limit,
+                radius,
+            } => {
+                let drag = self.touches.drag();
+                let delta = match axis {
+                    MouseAxis::Horizontal => drag.0,
+                    MouseAxis::Vertical => drag.1,
+                } / radius.into_inner();
+
+                if *limit {
+                    delta.clamp(-1.0, 1.0)
+                } else {
+                    delta
+                }
+            }
+            Axis::Pinch { radius } => {
+                self.touches.pinch() / radius.into_inner()
+            }
+            Axis::Gamepad {
+                axis,
+                dead_zone,
+                curve,
+            } => curve
+                .apply(self.gamepad_axis_value(*axis), dead_zone.into_inner()),
         }
     }
 
@@ -172,109 +775,566 @@ where
         self.physical_mouse_position
     }
 
+    /// Lines scrolled since the last end_frame with pixel scrolling
+    /// counted as lines, scaled by the WheelSettings. Scrolling up and
+    /// right are positive.
     pub fn mouse_wheel_value(&self, axis: MouseAxis) -> f32 {
-        match axis {
-            MouseAxis::Horizontal => self.mouse_wheel.0,
-            MouseAxis::Vertical => self.mouse_wheel.1,
-        }
+        let (lines, pixels) = match axis {
+            MouseAxis::Horizontal => (self.wheel_lines.0, self.wheel_pixels.0),
+            MouseAxis::Vertical => (self.wheel_lines.1, self.wheel_pixels.1),
+        };
+
+        (lines + pixels as f32 / self.wheel.pixels_per_line) * self.wheel.scale
+    }
+
+    /// Lines the mouse wheel moved since the last end_frame, unscaled.
+    pub fn mouse_wheel_lines(&self) -> (f32, f32) {
+        self.wheel_lines
+    }
+
+    /// Pixels touchpads scrolled since the last end_frame, unscaled.
+    pub fn mouse_wheel_pixels(&self) -> (f64, f64) {
+        self.wheel_pixels
+    }
+
+    pub fn wheel_settings(&self) -> &WheelSettings {
+        &self.wheel
+    }
+
+    pub fn set_wheel_settings(&mut self, settings: WheelSettings) {
+        self.wheel = settings;
     }
 
     pub fn new(bindings: Bindings<ActionId, AxisId>) -> Self {
         Self {
             bindings,
+            contexts: Vec::new(),
             keys: HashSet::new(),
             scan_codes: HashSet::new(),
             mouse_buttons: HashSet::new(),
+            pressed_keys: HashSet::new(),
+            released_keys: HashSet::new(),
+            pressed_scan_codes: HashSet::new(),
+            released_scan_codes: HashSet::new(),
+            pressed_mouse_buttons: HashSet::new(),
+            released_mouse_buttons: HashSet::new(),
+            touches: Touches::default(),
+            seconds: 0.0,
+            timing: TimingSettings::default(),
+            held: HashMap::new(),
+            repeated: HashSet::new(),
+            last_click: None,
+            double_clicks: HashSet::new(),
+            gamepads: BTreeSet::new(),
+            gamepad_buttons: HashSet::new(),
+            pressed_gamepad_buttons: HashSet::new(),
+            released_gamepad_buttons: HashSet::new(),
+            gamepad_axes: HashMap::new(),
             physical_mouse_position: None,
             mouse_position: None,
             last_mouse_position: None,
             mouse_delta: (0.0, 0.0),
-            mouse_wheel: (0.0, 0.0),
+            wheel_lines: (0.0, 0.0),
+            wheel_pixels: (0.0, 0.0),
+            wheel: WheelSettings::default(),
             modifiers: ModifiersState::default(),
+            characters: String::new(),
+            text_input: Vec::new(),
+            composition: None,
+            ime_enabled: false,
+            key_presses: Vec::new(),
+            rebinding: None,
+            rebound: None,
+            captured: HashSet::new(),
         }
     }
 
     pub fn update(&mut self, window: &Window, event: &Event<()>, hidpi: f32) {
+        if let Some(event) = InputEvent::from_winit(window.id(), event) {
+            self.apply(&event, hidpi);
+        }
+    }
+
+    /// Feeds in an event that did not come straight from the window, such
+    /// as one being played back from an InputRecording.
+    pub fn apply(&mut self, event: &InputEvent, hidpi: f32) {
         match *event {
-            Event::WindowEvent {
-                ref event,
-                window_id,
-            } if window_id == window.id() => match event {
-                WindowEvent::KeyboardInput {
-                    input:
-                        KeyboardInput {
-                            state,
-                            virtual_keycode: Some(key_code),
-                            scancode,
-                            ..
-                        },
-                    ..
-                } => {
-                    if *state == ElementState::Pressed {
-                        self.keys.insert(*key_code);
-                        self.scan_codes.insert(*scancode);
-                    } else {
-                        self.keys.remove(key_code);
-                        self.scan_codes.remove(scancode);
-                    }
-                }
-                WindowEvent::MouseInput { state, button, .. } => {
-                    if *state == ElementState::Pressed {
-                        self.mouse_buttons.insert(*button);
-                    } else {
-                        self.mouse_buttons.remove(button);
-                    }
-                }
-                WindowEvent::CursorMoved {
-                    position: PhysicalPosition { x, y },
-                    ..
-                } => {
-                    self.physical_mouse_position =
-                        Some(PhysicalPosition { x: *x, y: *y });
-                    self.mouse_position =
-                        Some(((*x as f32) * hidpi, (*y as f32) * hidpi));
+            InputEvent::Key {
+                key,
+                scan_code,
+                pressed,
+            } => self.key_input(key, scan_code, pressed),
+            InputEvent::MouseButton { button, pressed } => {
+                self.mouse_input(button, pressed)
+            }
+            InputEvent::CursorMoved { x, y } => {
+                self.physical_mouse_position = Some(PhysicalPosition { x, y });
+                self.mouse_position =
+                    Some(((x as f32) * hidpi, (y as f32) * hidpi));
+            }
+            InputEvent::FocusLost => {
+                // Everything held counts as let go so nothing sticks.
+                self.captured.clear();
+                self.released_keys.extend(self.keys.drain());
+                self.released_scan_codes.extend(self.scan_codes.drain());
+                self.released_mouse_buttons
+                    .extend(self.mouse_buttons.drain());
+                self.touches.cancel();
+                self.forget_released();
+            }
+            InputEvent::Modifiers(modifiers) => {
+                self.modifiers = modifiers;
+            }
+            InputEvent::Character(character) => {
+                self.characters.push(character);
+                self.text_input.push(TextInput::Character(character));
+            }
+            InputEvent::Ime(ref ime) => self.apply_ime(ime),
+            InputEvent::MouseMotion { dx, dy } => {
+                self.mouse_delta.0 -= dx;
+                self.mouse_delta.1 -= dy;
+            }
+            InputEvent::MouseWheel(MouseScrollDelta::LineDelta(dx, dy)) => {
+                self.wheel_lines.0 += dx;
+                self.wheel_lines.1 += dy;
+            }
+            InputEvent::MouseWheel(MouseScrollDelta::PixelDelta(
+                PhysicalPosition { x, y },
+            )) => {
+                self.wheel_pixels.0 += x;
+                self.wheel_pixels.1 += y;
+            }
+            InputEvent::Touch { id, phase, x, y } => {
+                let position = ((x as f32) * hidpi, (y as f32) * hidpi);
+                let tapped =
+                    self.touches.apply(id, phase, position, self.seconds);
+                self.track_gestures();
+
+                if !self.touches.settings.emulate_mouse {
+                    return;
                 }
-                WindowEvent::Focused(false) => {
-                    self.keys.clear();
-                    self.scan_codes.clear();
-                    self.mouse_buttons.clear();
+
+                // A lone finger moves the cursor and taps click.
+                let alone =
+                    matches!(self.touches.points(), [point] if point.id == id);
+                if alone || tapped {
+                    self.apply(&InputEvent::CursorMoved { x, y }, hidpi);
                 }
-                WindowEvent::ModifiersChanged(new_modifiers) => {
-                    self.modifiers = *new_modifiers;
+                if tapped {
+                    for pressed in [true, false] {
+                        self.apply(
+                            &InputEvent::MouseButton {
+                                button: MouseButton::Left,
+                                pressed,
+                            },
+                            hidpi,
+                        );
+                    }
                 }
-                _ => (),
-            },
-            Event::DeviceEvent { ref event, .. } => match *event {
-                DeviceEvent::MouseMotion { delta } => {
-                    self.mouse_delta.0 -= delta.0;
-                    self.mouse_delta.1 -= delta.1;
+            }
+            InputEvent::Gamepad(event) => self.apply_gamepad(event),
+        }
+    }
+
+    fn apply_ime(&mut self, ime: &ImeEvent) {
+        match ime {
+            ImeEvent::Enabled => self.ime_enabled = true,
+            ImeEvent::Preedit(composition) => {
+                self.composition =
+                    (!composition.text.is_empty()).then(|| composition.clone());
+                self.text_input
+                    .push(TextInput::Preedit(composition.clone()));
+            }
+            ImeEvent::Commit(text) => {
+                self.composition = None;
+                self.characters.push_str(text);
+                self.text_input.push(TextInput::Commit(text.clone()));
+            }
+            ImeEvent::Disabled => {
+                self.ime_enabled = false;
+                if self.composition.take().is_some() {
+                    self.text_input
+                        .push(TextInput::Preedit(Composition::default()));
                 }
-                DeviceEvent::MouseWheel {
-                    delta: MouseScrollDelta::LineDelta(dx, dy),
-                } => {
-                    if dx != 0.0 {
-                        self.mouse_wheel.0 = dx.signum();
-                    }
+            }
+        }
+    }
 
-                    if dy != 0.0 {
-                        self.mouse_wheel.1 = dy.signum();
-                    }
+    fn apply_gamepad(&mut self, event: GamepadEvent) {
+        match event {
+            GamepadEvent::Connected(id) => {
+                self.gamepads.insert(id);
+            }
+            GamepadEvent::Disconnected(id) => {
+                self.gamepads.remove(&id);
+                self.gamepad_axes.retain(|(pad, _), _| *pad != id);
+
+                let held: Vec<_> = self
+                    .gamepad_buttons
+                    .iter()
+                    .filter(|(pad, _)| *pad == id)
+                    .copied()
+                    .collect();
+                for (pad, button) in held {
+                    self.release_gamepad_button(pad, button);
                 }
-                DeviceEvent::MouseWheel {
-                    delta:
-                        MouseScrollDelta::PixelDelta(PhysicalPosition { x, y }),
-                } => {
-                    if x != 0.0 {
-                        self.mouse_wheel.0 = x.signum() as f32;
-                    }
+            }
+            GamepadEvent::Button {
+                id,
+                button,
+                pressed: true,
+            } => {
+                let newly_down = !self.is_gamepad_button_down(button);
 
-                    if y != 0.0 {
-                        self.mouse_wheel.1 = y.signum() as f32;
-                    }
+                if self.gamepad_buttons.insert((id, button))
+                    && newly_down
+                    && !self.capture(Button::Gamepad(button))
+                {
+                    self.pressed_gamepad_buttons.insert(button);
                 }
-                _ => (),
-            },
-            _ => (),
+                self.track(Button::Gamepad(button));
+            }
+            GamepadEvent::Button {
+                id,
+                button,
+                pressed: false,
+            } => self.release_gamepad_button(id, button),
+            GamepadEvent::Axis { id, axis, value } => {
+                self.gamepad_axes.insert((id, axis), value);
+            }
+        }
+    }
+
+    /// Lets go of button on the pad, counting it as released once no pad
+    /// holds it.
+    fn release_gamepad_button(&mut self, id: GamepadId, button: GamepadButton) {
+        if self.gamepad_buttons.remove(&(id, button))
+            && !self.is_gamepad_button_down(button)
+        {
+            self.released_gamepad_buttons.insert(button);
+            self.held.remove(&Button::Gamepad(button));
+        }
+    }
+
+    fn key_input(
+        &mut self,
+        key: winit::event::VirtualKeyCode,
+        scan_code: u32,
+        pressed: bool,
+    ) {
+        let button = Button::Key(key);
+
+        if pressed {
+            let captured = self.captured.contains(&button)
+                || (!self.keys.contains(&key) && self.capture(button));
+
+            if captured {
+                // OS repeats of the key are swallowed along with it.
+                self.captured.insert(button);
+                self.captured.insert(Button::ScanCode(scan_code));
+                return;
+            }
+
+            if self.keys.insert(key) {
+                self.pressed_keys.insert(key);
+            }
+            if self.scan_codes.insert(scan_code) {
+                self.pressed_scan_codes.insert(scan_code);
+            }
+            self.key_presses.push(key);
+        } else if self.captured.remove(&button) {
+            self.captured.remove(&Button::ScanCode(scan_code));
+        } else {
+            if self.keys.remove(&key) {
+                self.released_keys.insert(key);
+            }
+            if self.scan_codes.remove(&scan_code) {
+                self.released_scan_codes.insert(scan_code);
+            }
         }
+
+        self.track(button);
+        self.track(Button::ScanCode(scan_code));
+    }
+
+    fn mouse_input(
+        &mut self,
+        button: winit::event::MouseButton,
+        pressed: bool,
+    ) {
+        if pressed {
+            if self.mouse_buttons.contains(&button) {
+                return;
+            }
+
+            if self.capture(Button::Mouse(button)) {
+                self.captured.insert(Button::Mouse(button));
+            } else {
+                self.mouse_buttons.insert(button);
+                self.pressed_mouse_buttons.insert(button);
+                self.click(button);
+            }
+        } else if !self.captured.remove(&Button::Mouse(button))
+            && self.mouse_buttons.remove(&button)
+        {
+            self.released_mouse_buttons.insert(button);
+        }
+
+        self.track(Button::Mouse(button));
+    }
+}
+
+/// Whether button gets past every context in above.
+fn reaches<ActionId, AxisId>(
+    above: &[InputContext<ActionId, AxisId>],
+    button: Button,
+) -> bool
+where
+    ActionId: Clone + Eq + Hash + Send + Sync,
+    AxisId: Clone + Eq + Hash + Send + Sync,
+{
+    above.iter().all(|context| !context.consumes(button))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use winit::event::{MouseButton, VirtualKeyCode};
+
+    #[test]
+    fn rebinding_swallows_the_captured_key() {
+        let mut handler: InputHandler<u8, u8> =
+            InputHandler::new(Bindings::new());
+
+        handler.start_rebind(1);
+        handler.key_input(VirtualKeyCode::Q, 16, true);
+        assert_eq!(
+            handler.take_rebind(),
+            Some((1, Button::Key(VirtualKeyCode::Q)))
+        );
+
+        // Held down long enough for the OS to repeat it.
+        handler.key_input(VirtualKeyCode::Q, 16, true);
+        assert!(!handler.is_key_down(VirtualKeyCode::Q));
+        assert!(!handler.is_action_down(&1));
+        assert!(handler.key_presses().is_empty());
+
+        handler.end_frame();
+        handler.key_input(VirtualKeyCode::Q, 16, false);
+        assert!(!handler.is_key_released(VirtualKeyCode::Q));
+        assert!(!handler.is_scan_code_released(16));
+        assert!(!handler.is_action_released(&1));
+
+        handler.key_input(VirtualKeyCode::Q, 16, true);
+        assert!(handler.is_action_pressed(&1));
+        assert_eq!(handler.key_presses(), [VirtualKeyCode::Q]);
+    }
+
+    /// Handler with 1 on Space and 3 on the left mouse button, with
+    /// Space and the button held.
+    fn held() -> InputHandler<u8, u8> {
+        let mut bindings = Bindings::new();
+        bindings.insert_action(1, [VirtualKeyCode::Space.into()]);
+        bindings.insert_action(3, [MouseButton::Left.into()]);
+
+        let mut handler = InputHandler::new(bindings);
+        handler.key_input(VirtualKeyCode::Space, 57, true);
+        handler.mouse_input(MouseButton::Left, true);
+        handler
+    }
+
+    fn context(name: &str, action: u8, button: Button) -> InputContext<u8, u8> {
+        let mut bindings = Bindings::new();
+        bindings.insert_action(action, [button]);
+        InputContext::new(name, bindings)
+    }
+
+    #[test]
+    fn consume_all_hides_lower_bindings() {
+        let mut handler = held();
+        handler.push_context(
+            context("menu", 2, MouseButton::Left.into())
+                .with_consume(Consume::All),
+        );
+
+        assert!(handler.is_action_down(&2));
+        assert!(!handler.is_action_down(&1));
+        assert!(!handler.is_action_down(&3));
+
+        handler.remove_context("menu");
+        assert!(handler.is_action_down(&1));
+        assert!(handler.is_action_down(&3));
+    }
+
+    #[test]
+    fn unbound_buttons_fall_through_to_the_context_below() {
+        let mut handler = held();
+        handler.push_context(context("board", 2, MouseButton::Left.into()));
+
+        // The board keeps the button it binds but lets Space through.
+        assert!(handler.is_action_down(&2));
+        assert!(!handler.is_action_down(&3));
+        assert!(handler.is_action_down(&1));
+
+        handler.push_context(
+            context("hud", 4, MouseButton::Left.into())
+                .with_consume(Consume::Nothing),
+        );
+        assert!(handler.is_action_down(&4));
+        assert!(handler.is_action_down(&2));
+    }
+
+    #[test]
+    fn text_entry_turns_off_the_actions_below() {
+        let mut handler = held();
+        handler.push_context(
+            InputContext::new("chat", Bindings::new()).with_text_entry(),
+        );
+
+        assert!(handler.combinations(&1).is_empty());
+        assert!(handler.combinations(&3).is_empty());
+        assert!(!handler.is_action_down(&3));
+
+        // A context pushed over the chat is still read.
+        handler.push_context(context("menu", 2, MouseButton::Left.into()));
+        assert_eq!(
+            handler.combinations(&2),
+            [[Button::Mouse(MouseButton::Left)].as_slice()]
+        );
+        assert!(handler.is_action_down(&2));
+    }
+
+    fn click(handler: &mut InputHandler<u8, u8>, seconds: f32, x: f64) {
+        handler.set_time(seconds);
+        handler.apply(&InputEvent::CursorMoved { x, y: 0.0 }, 1.0);
+        handler.mouse_input(MouseButton::Left, true);
+        handler.mouse_input(MouseButton::Left, false);
+    }
+
+    #[test]
+    fn double_clicks_need_to_be_quick_and_close() {
+        let mut handler: InputHandler<u8, u8> =
+            InputHandler::new(Bindings::new());
+        let mut double_clicks = Vec::new();
+
+        // Quick and close, then a third click that starts over.
+        for (seconds, x) in [(0.0, 0.0), (0.3, 3.0), (0.5, 3.0)] {
+            click(&mut handler, seconds, x);
+            double_clicks.push(handler.is_double_click(MouseButton::Left));
+            handler.end_frame();
+        }
+        // Too slow, then too far.
+        for (seconds, x) in [(2.0, 0.0), (2.5, 0.0), (2.6, 10.0)] {
+            click(&mut handler, seconds, x);
+            double_clicks.push(handler.is_double_click(MouseButton::Left));
+            handler.end_frame();
+        }
+
+        assert_eq!(double_clicks, [false, true, false, false, false, false]);
+    }
+
+    #[test]
+    fn hold_duration_counts_from_the_press() {
+        let mut handler = held();
+        let space = Button::Key(VirtualKeyCode::Space);
+
+        handler.set_time(1.5);
+        assert_eq!(handler.hold_duration(space), 1.5);
+        assert_eq!(handler.action_hold_duration(&1), 1.5);
+
+        handler.key_input(VirtualKeyCode::Space, 57, false);
+        assert_eq!(handler.hold_duration(space), 0.0);
+        assert_eq!(handler.action_hold_duration(&1), 0.0);
+
+        handler.end_frame();
+        handler.set_time(2.0);
+        handler.key_input(VirtualKeyCode::Space, 57, true);
+        handler.set_time(2.25);
+        assert_eq!(handler.action_hold_duration(&1), 0.25);
+    }
+
+    #[test]
+    fn wheel_deltas_add_up_over_the_frame() {
+        let mut handler: InputHandler<u8, u8> =
+            InputHandler::new(Bindings::new());
+        let pixels = |x, y| {
+            InputEvent::MouseWheel(MouseScrollDelta::PixelDelta(
+                PhysicalPosition { x, y },
+            ))
+        };
+
+        handler.apply(
+            &InputEvent::MouseWheel(MouseScrollDelta::LineDelta(0.0, 1.0)),
+            1.0,
+        );
+        handler.apply(
+            &InputEvent::MouseWheel(MouseScrollDelta::LineDelta(0.5, 2.0)),
+            1.0,
+        );
+        handler.apply(&pixels(10.0, -30.0), 1.0);
+        handler.apply(&pixels(0.0, -10.0), 1.0);
+
+        assert_eq!(handler.mouse_wheel_lines(), (0.5, 3.0));
+        assert_eq!(handler.mouse_wheel_pixels(), (10.0, -40.0));
+        // 40 pixels up is two lines back at 20 pixels a line.
+        assert_eq!(handler.mouse_wheel_value(MouseAxis::Vertical), 1.0);
+        assert_eq!(handler.mouse_wheel_value(MouseAxis::Horizontal), 1.0);
+
+        handler.set_wheel_settings(WheelSettings {
+            pixels_per_line: 10.0,
+            scale: 2.0,
+        });
+        assert_eq!(handler.mouse_wheel_value(MouseAxis::Vertical), -2.0);
+
+        handler.end_frame();
+        assert_eq!(handler.mouse_wheel_lines(), (0.0, 0.0));
+        assert_eq!(handler.mouse_wheel_pixels(), (0.0, 0.0));
+        assert_eq!(handler.mouse_wheel_value(MouseAxis::Vertical), 0.0);
+    }
+
+    #[test]
+    fn typed_text_lasts_one_frame() {
+        let mut handler: InputHandler<u8, u8> =
+            InputHandler::new(Bindings::new());
+        let preedit = |text: &str| Composition {
+            text: text.to_owned(),
+            cursor: Some((text.len(), text.len())),
+        };
+
+        handler.apply(&InputEvent::Character('a'), 1.0);
+        handler.apply(&InputEvent::Ime(ImeEvent::Enabled), 1.0);
+        handler.apply(&InputEvent::Ime(ImeEvent::Preedit(preedit("に"))), 1.0);
+        assert_eq!(handler.characters(), "a");
+        assert_eq!(handler.composition(), Some(&preedit("に")));
+        assert_eq!(
+            handler.text_input(),
+            [TextInput::Character('a'), TextInput::Preedit(preedit("に"))]
+        );
+
+        // The composition stays until the IME is done with it.
+        handler.end_frame();
+        assert_eq!(handler.characters(), "");
+        assert!(handler.text_input().is_empty());
+        assert_eq!(handler.composition(), Some(&preedit("に")));
+
+        handler.apply(
+            &InputEvent::Ime(ImeEvent::Preedit(Composition::default())),
+            1.0,
+        );
+        handler.apply(&InputEvent::Ime(ImeEvent::Commit("日".to_owned())), 1.0);
+        assert_eq!(handler.characters(), "日");
+        assert_eq!(handler.composition(), None);
+        assert_eq!(
+            handler.text_input(),
+            [
+                TextInput::Preedit(Composition::default()),
+                TextInput::Commit("日".to_owned())
+            ]
+        );
+
+        handler.end_frame();
+        assert_eq!(handler.characters(), "");
+        assert!(handler.text_input().is_empty());
+        assert!(handler.is_ime_enabled());
     }
 }
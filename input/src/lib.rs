@@ -1,11 +1,38 @@
 mod axis;
 mod bindings;
 mod button;
+mod context;
+mod error;
+mod event;
+mod frame_stats;
 mod frame_time;
+mod gamepad;
 mod handler;
+mod recording;
+mod text;
+mod timer;
+mod timing;
+mod touch;
 
-pub use axis::{Axis, MouseAxis};
-pub use bindings::Bindings;
+pub use axis::{Axis, MouseAxis, WheelSettings};
+pub use bindings::{BindingProblem, Bindings};
 pub use button::Button;
-pub use frame_time::FrameTime;
+pub use context::{Consume, InputContext};
+pub use error::InputError;
+pub use event::InputEvent;
+pub use frame_stats::{FrameStats, DEFAULT_STATS_FRAMES};
+pub use frame_time::{Clock, FrameTime, ManualClock, SystemClock};
+#[cfg(feature = "gilrs")]
+pub use gamepad::GilrsSource;
+pub use gamepad::{
+    FakeGamepad, GamepadAxis, GamepadButton, GamepadEvent, GamepadId,
+    GamepadSource, ResponseCurve,
+};
 pub use handler::InputHandler;
+pub use recording::{
+    InputPlayback, InputRecorder, InputRecording, RecordedEvent,
+};
+pub use text::{Composition, ImeEvent, TextInput};
+pub use timer::{Scheduler, Timer, TimerHandle};
+pub use timing::TimingSettings;
+pub use touch::{Gesture, GestureSettings, TouchPoint};
@@ -0,0 +1,382 @@
+use super::error::InputError;
+use super::event::InputEvent;
+use super::frame_time::FrameTime;
+use super::handler::InputHandler;
+use super::text::{Composition, ImeEvent};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::hash::Hash;
+use std::path::Path;
+use winit::event::{Event, MouseButton, VirtualKeyCode};
+use winit::window::Window;
+
+/// Frame length scripted events are timed with, and that playback guesses
+/// for frames without events.
+const SCRIPT_FRAME_SECONDS: f32 = 1.0 / 60.0;
+
+/// An InputEvent along with the frame it was fed in on.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    pub frame: u64,
+    /// Seconds since the start when it came in. Playback sets the
+    /// InputHandler time to this before feeding the event in.
+    pub seconds: f32,
+    pub event: InputEvent,
+}
+
+/// Every event fed to an InputHandler in order. Can be recorded with an
+/// InputRecorder or scripted by hand.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct InputRecording {
+    /// Scale the cursor positions are fed in with.
+    pub hidpi: f32,
+    pub events: Vec<RecordedEvent>,
+}
+
+impl Default for InputRecording {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+impl InputRecording {
+    pub fn new(hidpi: f32) -> Self {
+        Self {
+            hidpi,
+            events: Vec::new(),
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, InputError> {
+        Ok(toml::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), InputError> {
+        let path = path.as_ref();
+
+        if let Some(dir) =
+            path.parent().filter(|dir| !dir.as_os_str().is_empty())
+        {
+            fs::create_dir_all(dir)?;
+        }
+
+        fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn push(&mut self, frame: u64, seconds: f32, event: InputEvent) {
+        self.events.push(RecordedEvent {
+            frame,
+            seconds,
+            event,
+        });
+    }
+
+    /// Adds an event timed as if every frame took a 60th of a second.
+    pub fn push_scripted(&mut self, frame: u64, event: InputEvent) {
+        self.push(frame, frame as f32 * SCRIPT_FRAME_SECONDS, event);
+    }
+
+    /// Moves the cursor to x, y in physical pixels and clicks button, all
+    /// within the frame.
+    pub fn click(&mut self, frame: u64, x: f64, y: f64, button: MouseButton) {
+        self.push_scripted(frame, InputEvent::CursorMoved { x, y });
+        for pressed in [true, false] {
+            self.push_scripted(
+                frame,
+                InputEvent::MouseButton { button, pressed },
+            );
+        }
+    }
+
+    /// Presses and lets go of key within the frame.
+    pub fn tap_key(&mut self, frame: u64, key: VirtualKeyCode) {
+        for pressed in [true, false] {
+            self.push_scripted(
+                frame,
+                InputEvent::Key {
+                    key,
+                    scan_code: 0,
+                    pressed,
+                },
+            );
+        }
+    }
+
+    /// Types text within the frame.
+    pub fn type_text(&mut self, frame: u64, text: &str) {
+        for character in text.chars() {
+            self.push_scripted(frame, InputEvent::Character(character));
+        }
+    }
+
+    /// Composes text with the IME and commits it within the frame.
+    pub fn commit_text(&mut self, frame: u64, text: &str) {
+        let end = text.len();
+        for ime in [
+            ImeEvent::Enabled,
+            ImeEvent::Preedit(Composition {
+                text: text.to_owned(),
+                cursor: Some((end, end)),
+            }),
+            ImeEvent::Preedit(Composition::default()),
+            ImeEvent::Commit(text.to_owned()),
+        ] {
+            self.push_scripted(frame, InputEvent::Ime(ime));
+        }
+    }
+
+    /// Frame of the last event.
+    pub fn last_frame(&self) -> Option<u64> {
+        self.events.last().map(|event| event.frame)
+    }
+}
+
+/// Records the events fed to an InputHandler along with the frame they
+/// came in on.
+#[derive(Clone, Debug, Default)]
+pub struct InputRecorder {
+    recording: InputRecording,
+}
+
+impl InputRecorder {
+    pub fn new(hidpi: f32) -> Self {
+        Self {
+            recording: InputRecording::new(hidpi),
+        }
+    }
+
+    /// Records the event if it is one the InputHandler reads. Call next to
+    /// InputHandler::update with the same event.
+    pub fn record_winit(
+        &mut self,
+        window: &Window,
+        event: &Event<()>,
+        frame_time: &FrameTime,
+    ) {
+        if let Some(event) = InputEvent::from_winit(window.id(), event) {
+            self.record(event, frame_time);
+        }
+    }
+
+    pub fn record(&mut self, event: InputEvent, frame_time: &FrameTime) {
+        self.recording
+            .push(frame_time.frame(), frame_time.seconds(), event);
+    }
+
+    pub fn recording(&self) -> &InputRecording {
+        &self.recording
+    }
+
+    pub fn finish(self) -> InputRecording {
+        self.recording
+    }
+}
+
+/// Feeds an InputRecording back into an InputHandler frame by frame. No
+/// window is needed so it can drive tests.
+#[derive(Clone, Debug)]
+pub struct InputPlayback {
+    recording: InputRecording,
+    /// Index of the next event to feed in.
+    next: usize,
+    /// Frame and seconds of the last event fed in.
+    last: (u64, f32),
+}
+
+impl InputPlayback {
+    pub fn new(recording: InputRecording) -> Self {
+        Self {
+            recording,
+            next: 0,
+            last: (0, 0.0),
+        }
+    }
+
+    /// Feeds in every event recorded up to and including frame, setting
+    /// the InputHandler time to when each came in. Call once per frame
+    /// before input is read, with end_frame called after as usual. The
+    /// playback keeps the time so InputHandler::set_time should not be
+    /// called while it runs.
+    pub fn play_frame<ActionId, AxisId>(
+        &mut self,
+        handler: &mut InputHandler<ActionId, AxisId>,
+        frame: u64,
+    ) where
+        ActionId: Clone + Eq + Hash + Send + Sync,
+        AxisId: Clone + Eq + Hash + Send + Sync,
+    {
+        while let Some(event) = self.recording.events.get(self.next) {
+            if event.frame > frame {
+                break;
+            }
+
+            handler.set_time(event.seconds);
+            handler.apply(&event.event, self.recording.hidpi);
+            self.last = (event.frame, event.seconds);
+            self.next += 1;
+        }
+
+        handler.set_time(self.seconds(frame));
+    }
+
+    /// Recorded time of frame. Frames without events are guessed at a
+    /// 60th of a second each from the last event, up to the next one.
+    pub fn seconds(&self, frame: u64) -> f32 {
+        let (last_frame, last_seconds) = self.last;
+        let seconds = last_seconds
+            + frame.saturating_sub(last_frame) as f32 * SCRIPT_FRAME_SECONDS;
+
+        self.recording
+            .events
+            .get(self.next)
+            .map_or(seconds, |next| seconds.min(next.seconds))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.recording.events.len()
+    }
+
+    pub fn recording(&self) -> &InputRecording {
+        &self.recording
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Bindings, Button, ManualClock};
+    use std::collections::HashMap;
+
+    #[derive(
+        Copy, Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize,
+    )]
+    enum Action {
+        Jump,
+        Select,
+    }
+
+    type Handler = InputHandler<Action, Action>;
+
+    fn handler() -> Handler {
+        let mut bindings = Bindings::new();
+        bindings
+            .insert_action(Action::Jump, [Button::Key(VirtualKeyCode::Space)]);
+        bindings
+            .insert_action(Action::Select, [Button::Mouse(MouseButton::Left)]);
+        InputHandler::new(bindings)
+    }
+
+    /// What the game would see of the actions this frame. Repeats are
+    /// counted apart since the frame one lands on can differ by rounding.
+    fn read(handler: &Handler, repeats: &mut usize) -> Vec<String> {
+        *repeats += usize::from(handler.is_action_repeated(&Action::Jump));
+
+        let mut seen = Vec::new();
+        for action in [Action::Jump, Action::Select] {
+            if handler.is_action_pressed(&action) {
+                seen.push(format!("{action:?} pressed"));
+            }
+            if handler.is_action_down(&action) {
+                let held = handler.action_hold_duration(&action);
+                seen.push(format!("{action:?} held {held:.2}"));
+            }
+            if handler.is_action_released(&action) {
+                seen.push(format!("{action:?} released"));
+            }
+        }
+        if handler.is_double_click(MouseButton::Left) {
+            seen.push("double click".to_owned());
+        }
+        seen
+    }
+
+    #[test]
+    fn playback_fires_the_recorded_actions() {
+        let key = |pressed| InputEvent::Key {
+            key: VirtualKeyCode::Space,
+            scan_code: 57,
+            pressed,
+        };
+        let click = |pressed| InputEvent::MouseButton {
+            button: MouseButton::Left,
+            pressed,
+        };
+        let mut script: HashMap<u64, Vec<InputEvent>> = HashMap::new();
+        script.insert(5, vec![key(true)]);
+        script.insert(50, vec![key(false)]);
+        script.insert(60, vec![InputEvent::CursorMoved { x: 8.0, y: 8.0 }]);
+        // Two clicks close together then two a second apart.
+        for frame in [62, 70, 120, 180] {
+            script.insert(frame, vec![click(true), click(false)]);
+        }
+
+        let clock = ManualClock::new();
+        let mut frame_time = FrameTime::with_clock(clock.clone());
+        let mut recorder = InputRecorder::new(1.0);
+        let mut live = handler();
+        let mut live_seen = Vec::new();
+        let mut live_repeats = 0;
+
+        for frame in 0..200 {
+            for event in script.remove(&frame).unwrap_or_default() {
+                live.apply(&event, 1.0);
+                recorder.record(event, &frame_time);
+            }
+            live_seen.push(read(&live, &mut live_repeats));
+            live.end_frame();
+
+            clock.advance(SCRIPT_FRAME_SECONDS);
+            frame_time.update();
+            live.set_time(frame_time.seconds());
+        }
+
+        let mut playback = InputPlayback::new(recorder.finish());
+        let mut replayed = handler();
+        let mut replayed_seen = Vec::new();
+        let mut replayed_repeats = 0;
+
+        for frame in 0..200 {
+            playback.play_frame(&mut replayed, frame);
+            replayed_seen.push(read(&replayed, &mut replayed_repeats));
+            replayed.end_frame();
+        }
+
+        assert!(playback.is_finished());
+        let count = |seen: &[Vec<String>], what: &str| {
+            seen.iter().flatten().filter(|s| s.as_str() == what).count()
+        };
+        assert_eq!(count(&live_seen, "Jump released"), 1);
+        assert!(live_repeats > 0);
+        assert_eq!(count(&live_seen, "double click"), 1);
+        assert_eq!(replayed_seen, live_seen);
+        assert_eq!(replayed_repeats, live_repeats);
+    }
+
+    #[test]
+    fn save_and_load_keep_ime_events() {
+        let mut recording = InputRecording::new(2.0);
+        recording.click(1, 10.0, 20.0, MouseButton::Left);
+        recording.tap_key(2, VirtualKeyCode::A);
+        recording.type_text(3, "hi");
+        recording.commit_text(4, "日本");
+        recording.push_scripted(
+            5,
+            InputEvent::Ime(ImeEvent::Preedit(Composition {
+                text: "語".to_owned(),
+                cursor: None,
+            })),
+        );
+        recording.push_scripted(5, InputEvent::Ime(ImeEvent::Disabled));
+
+        let path = std::env::temp_dir().join(format!(
+            "ascending_input_recording_{}.toml",
+            std::process::id()
+        ));
+        recording.save(&path).unwrap();
+        let loaded = InputRecording::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.unwrap(), recording);
+    }
+}
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use winit::event::Ime;
+
+/// Text an IME is still composing. It is not part of the text field yet
+/// and is shown at the caret until the IME commits or cancels it.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Composition {
+    pub text: String,
+    /// Byte range of text the IME has its cursor over, or None when the
+    /// cursor should be hidden. Left out of saved recordings when None.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<(usize, usize)>,
+}
+
+/// Piece of text input in the order it came in.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum TextInput {
+    /// Typed on the keyboard. Includes control characters such as
+    /// backspace which text fields should skip.
+    Character(char),
+    /// Text the IME finished composing, to insert at the caret.
+    Commit(String),
+    /// The IME changed what it is composing. The text is empty once the
+    /// composition is cancelled or committed.
+    Preedit(Composition),
+}
+
+/// IME event as the InputHandler reads it. Stands in for winit's Ime,
+/// whose hidden cursor cannot be saved to a recording.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ImeEvent {
+    Enabled,
+    /// The IME changed what it is composing, empty once cancelled.
+    Preedit(Composition),
+    Commit(String),
+    Disabled,
+}
+
+impl From<&Ime> for ImeEvent {
+    fn from(ime: &Ime) -> Self {
+        match ime {
+            Ime::Enabled => ImeEvent::Enabled,
+            Ime::Preedit(text, cursor) => ImeEvent::Preedit(Composition {
+                text: text.clone(),
+                cursor: *cursor,
+            }),
+            Ime::Commit(text) => ImeEvent::Commit(text.clone()),
+            Ime::Disabled => ImeEvent::Disabled,
+        }
+    }
+}
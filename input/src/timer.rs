@@ -0,0 +1,290 @@
+use serde::{Deserialize, Serialize};
+
+/// Goes off once a time is reached, and again every interval after if it
+/// repeats. Small enough to keep in a component. Times are in whichever
+/// seconds are passed to tick, such as FrameTime::seconds or
+/// FrameTime::scaled_seconds.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Timer {
+    /// Seconds it goes off at next.
+    due: f32,
+    /// Seconds between repeats, None if it only goes off once.
+    interval: Option<f32>,
+    finished: bool,
+}
+
+impl Timer {
+    /// Goes off once at due seconds.
+    pub fn once(due: f32) -> Self {
+        Self {
+            due,
+            interval: None,
+            finished: false,
+        }
+    }
+
+    /// Goes off at due seconds and then every interval seconds after.
+    pub fn repeating(due: f32, interval: f32) -> Self {
+        Self {
+            due,
+            interval: Some(interval.max(f32::EPSILON)),
+            finished: false,
+        }
+    }
+
+    /// Returns true if it went off since the last tick. A repeating timer
+    /// moves on to its next time after seconds, skipping any missed by a
+    /// long frame rather than going off for each.
+    pub fn tick(&mut self, seconds: f32) -> bool {
+        if self.finished || seconds < self.due {
+            return false;
+        }
+
+        match self.interval {
+            Some(interval) => {
+                let missed = ((seconds - self.due) / interval).floor() + 1.0;
+                self.due += missed * interval;
+            }
+            None => self.finished = true,
+        }
+
+        true
+    }
+
+    /// Seconds it goes off at next.
+    pub fn due(&self) -> f32 {
+        self.due
+    }
+
+    /// Seconds left from seconds until it goes off, 0.0 once finished.
+    pub fn remaining(&self, seconds: f32) -> f32 {
+        if self.finished {
+            0.0
+        } else {
+            (self.due - seconds).max(0.0)
+        }
+    }
+
+    pub fn is_repeating(&self) -> bool {
+        self.interval.is_some()
+    }
+
+    /// A one shot timer that went off. Repeating timers never finish.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+}
+
+/// Names a timer in a Scheduler so it can be looked up or cancelled.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Hash,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Serialize,
+    Deserialize,
+)]
+pub struct TimerHandle(u64);
+
+#[derive(Clone, Debug)]
+struct Scheduled<E> {
+    handle: TimerHandle,
+    timer: Timer,
+    event: E,
+}
+
+/// Hands back events once their timers go off. Advanced once a frame with
+/// update, and times added with after or every count from the seconds it
+/// was last given.
+#[derive(Clone, Debug)]
+pub struct Scheduler<E> {
+    seconds: f32,
+    next_handle: u64,
+    /// In the order they were added.
+    scheduled: Vec<Scheduled<E>>,
+}
+
+impl<E> Default for Scheduler<E> {
+    fn default() -> Self {
+        Self {
+            seconds: 0.0,
+            next_handle: 0,
+            scheduled: Vec::new(),
+        }
+    }
+}
+
+impl<E: Clone> Scheduler<E> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Seconds last passed to update.
+    pub fn seconds(&self) -> f32 {
+        self.seconds
+    }
+
+    /// Sends event once at due seconds.
+    pub fn at(&mut self, due: f32, event: E) -> TimerHandle {
+        self.add(Timer::once(due), event)
+    }
+
+    /// Sends event once delay seconds from now.
+    pub fn after(&mut self, delay: f32, event: E) -> TimerHandle {
+        self.add(Timer::once(self.seconds + delay), event)
+    }
+
+    /// Sends event every interval seconds, starting an interval from now.
+    pub fn every(&mut self, interval: f32, event: E) -> TimerHandle {
+        self.add(Timer::repeating(self.seconds + interval, interval), event)
+    }
+
+    /// Adds a timer made by hand, such as one repeating from a set time.
+    pub fn add(&mut self, timer: Timer, event: E) -> TimerHandle {
+        let handle = TimerHandle(self.next_handle);
+        self.next_handle += 1;
+        self.scheduled.push(Scheduled {
+            handle,
+            timer,
+            event,
+        });
+        handle
+    }
+
+    /// Stops the timer, returning its event if it was still scheduled.
+    pub fn cancel(&mut self, handle: TimerHandle) -> Option<E> {
+        let index = self.index(handle)?;
+        Some(self.scheduled.remove(index).event)
+    }
+
+    pub fn clear(&mut self) {
+        self.scheduled.clear();
+    }
+
+    /// Whether the timer is still waiting to go off. One shot timers stop
+    /// waiting once they went off or were cancelled.
+    pub fn is_scheduled(&self, handle: TimerHandle) -> bool {
+        self.index(handle).is_some()
+    }
+
+    /// Seconds until the timer goes off next.
+    pub fn remaining(&self, handle: TimerHandle) -> Option<f32> {
+        self.index(handle)
+            .map(|index| self.scheduled[index].timer.remaining(self.seconds))
+    }
+
+    pub fn len(&self) -> usize {
+        self.scheduled.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scheduled.is_empty()
+    }
+
+    /// Moves time on to seconds and returns the events that went off, in
+    /// the order they were due. Events due at the same time keep the order
+    /// they were added in.
+    pub fn update(&mut self, seconds: f32) -> Vec<(TimerHandle, E)> {
+        self.seconds = seconds;
+
+        let mut fired = Vec::new();
+        for scheduled in &mut self.scheduled {
+            let due = scheduled.timer.due();
+            if scheduled.timer.tick(seconds) {
+                fired.push((due, scheduled.handle, scheduled.event.clone()));
+            }
+        }
+        self.scheduled
+            .retain(|scheduled| !scheduled.timer.is_finished());
+
+        fired.sort_by(|a, b| a.0.total_cmp(&b.0));
+        fired
+            .into_iter()
+            .map(|(_, handle, event)| (handle, event))
+            .collect()
+    }
+
+    fn index(&self, handle: TimerHandle) -> Option<usize> {
+        self.scheduled
+            .iter()
+            .position(|scheduled| scheduled.handle == handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_shot_goes_off_once_and_repeating_keeps_going() {
+        let mut once = Timer::once(1.0);
+        let mut repeating = Timer::repeating(1.0, 0.5);
+
+        assert!(!once.tick(0.5));
+        assert!(!repeating.tick(0.5));
+        assert!(once.tick(1.0));
+        assert!(repeating.tick(1.0));
+        assert!(once.is_finished());
+        assert_eq!(repeating.due(), 1.5);
+
+        assert!(!once.tick(2.0));
+        assert!(repeating.tick(1.5));
+        assert!(!repeating.is_finished());
+        assert_eq!(once.remaining(2.0), 0.0);
+        assert_eq!(repeating.remaining(1.75), 0.25);
+    }
+
+    #[test]
+    fn a_long_step_goes_off_once_and_skips_the_missed_times() {
+        let mut timer = Timer::repeating(1.0, 1.0);
+
+        assert!(timer.tick(4.5));
+        assert_eq!(timer.due(), 5.0);
+        assert!(!timer.tick(4.75));
+
+        let mut scheduler = Scheduler::new();
+        scheduler.every(1.0, "beat");
+        assert_eq!(scheduler.update(3.5).len(), 1);
+        assert_eq!(scheduler.remaining(TimerHandle(0)), Some(0.5));
+    }
+
+    #[test]
+    fn cancelling_stops_the_timer_and_stale_handles_do_nothing() {
+        let mut scheduler = Scheduler::new();
+        let first = scheduler.after(1.0, "first");
+        let second = scheduler.after(1.0, "second");
+
+        assert_eq!(scheduler.cancel(first), Some("first"));
+        assert_eq!(scheduler.cancel(first), None);
+        assert!(!scheduler.is_scheduled(first));
+
+        // Handles are never reused, so an old one cannot cancel a new timer.
+        let third = scheduler.after(2.0, "third");
+        assert_ne!(third, first);
+        assert_eq!(scheduler.cancel(first), None);
+
+        assert_eq!(scheduler.update(1.0), [(second, "second")]);
+        assert_eq!(scheduler.cancel(second), None);
+        assert_eq!(scheduler.len(), 1);
+        assert_eq!(scheduler.update(2.0), [(third, "third")]);
+        assert!(scheduler.is_empty());
+    }
+
+    #[test]
+    fn events_due_in_the_same_frame_go_off_in_due_order() {
+        let mut scheduler = Scheduler::new();
+        let late = scheduler.at(0.75, "late");
+        let early = scheduler.at(0.5, "early");
+        let tied = scheduler.at(0.75, "tied");
+
+        assert!(scheduler.update(0.25).is_empty());
+        assert_eq!(
+            scheduler.update(1.0),
+            [(early, "early"), (late, "late"), (tied, "tied")]
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+
+/// How close clicks are to count as a double click and how held buttons
+/// repeat. Times are in seconds as passed to InputHandler::set_time.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TimingSettings {
+    /// Longest gap between the clicks of a double click.
+    pub double_click_time: f32,
+    /// How far the cursor can move between the clicks of a double click,
+    /// in the same units as the mouse position.
+    pub double_click_distance: f32,
+    /// How long a button is held before it starts repeating.
+    pub repeat_delay: f32,
+    /// Repeats a second once it starts. 0.0 turns repeating off.
+    pub repeat_rate: f32,
+}
+
+impl Default for TimingSettings {
+    fn default() -> Self {
+        Self {
+            double_click_time: 0.4,
+            double_click_distance: 4.0,
+            repeat_delay: 0.5,
+            repeat_rate: 20.0,
+        }
+    }
+}
+
+/// When a held button went down and when it repeats next.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(super) struct Held {
+    pub since: f32,
+    pub next_repeat: f32,
+}
+
+impl Held {
+    pub fn new(seconds: f32, settings: &TimingSettings) -> Self {
+        Self {
+            since: seconds,
+            next_repeat: seconds + settings.repeat_delay,
+        }
+    }
+
+    /// Moves next_repeat past seconds, returning true if a repeat was
+    /// due. Repeats missed by a long frame are skipped rather than all
+    /// firing at once.
+    pub fn repeat(&mut self, seconds: f32, settings: &TimingSettings) -> bool {
+        if settings.repeat_rate <= 0.0 || seconds < self.next_repeat {
+            return false;
+        }
+
+        let interval = 1.0 / settings.repeat_rate;
+        while self.next_repeat <= seconds {
+            self.next_repeat += interval;
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(repeat_rate: f32) -> TimingSettings {
+        TimingSettings {
+            repeat_delay: 0.5,
+            repeat_rate,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn held_buttons_repeat_at_the_rate_after_the_delay() {
+        let settings = settings(4.0);
+        let mut held = Held::new(1.0, &settings);
+
+        assert!(!held.repeat(1.25, &settings));
+        assert!(held.repeat(1.5, &settings));
+        assert!(!held.repeat(1.5, &settings));
+        assert!(!held.repeat(1.7, &settings));
+        assert!(held.repeat(1.75, &settings));
+        assert_eq!(held.next_repeat, 2.0);
+    }
+
+    #[test]
+    fn a_long_frame_repeats_once() {
+        let settings = settings(4.0);
+        let mut held = Held::new(0.0, &settings);
+
+        assert!(held.repeat(3.1, &settings));
+        assert_eq!(held.next_repeat, 3.25);
+        assert!(!held.repeat(3.2, &settings));
+        assert_eq!(held.since, 0.0);
+    }
+
+    #[test]
+    fn a_zero_rate_never_repeats() {
+        let settings = settings(0.0);
+        let mut held = Held::new(0.0, &settings);
+
+        assert!(!held.repeat(10.0, &settings));
+    }
+}
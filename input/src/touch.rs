@@ -0,0 +1,396 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use winit::event::TouchPhase;
+
+/// Gestures picked out of the touches. Each can be bound like any other
+/// button.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Gesture {
+    /// Quick touch and lift without moving. Pressed and released in the
+    /// same frame.
+    Tap,
+    /// Second tap close to the first soon after it. The second tap still
+    /// counts as a Tap too.
+    DoubleTap,
+    /// One finger held still. Held until it lifts.
+    LongPress,
+    /// One finger moving. Held until it lifts.
+    Drag,
+    /// Two fingers moving apart or together. Held until one lifts.
+    Pinch,
+}
+
+impl Gesture {
+    pub const ALL: [Gesture; 5] = [
+        Gesture::Tap,
+        Gesture::DoubleTap,
+        Gesture::LongPress,
+        Gesture::Drag,
+        Gesture::Pinch,
+    ];
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GestureSettings {
+    /// Longest a touch can last and still be a tap.
+    pub tap_time: f32,
+    /// Longest gap between the taps of a double tap.
+    pub double_tap_time: f32,
+    /// How long a finger is held still to become a long press.
+    pub long_press_time: f32,
+    /// How far a finger can move and still count as still, in the same
+    /// units as the mouse position.
+    pub move_tolerance: f32,
+    /// One finger moves the cursor and taps click the left mouse button.
+    pub emulate_mouse: bool,
+}
+
+impl Default for GestureSettings {
+    fn default() -> Self {
+        Self {
+            tap_time: 0.25,
+            double_tap_time: 0.35,
+            long_press_time: 0.5,
+            move_tolerance: 12.0,
+            emulate_mouse: true,
+        }
+    }
+}
+
+/// Finger on the screen.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TouchPoint {
+    pub id: u64,
+    pub position: (f32, f32),
+    pub start_position: (f32, f32),
+    /// Seconds it was put down at.
+    pub start_time: f32,
+    /// Moved past the move tolerance or shared the screen with another
+    /// finger, so it can no longer be a tap or long press.
+    pub moved: bool,
+}
+
+/// Tracks the fingers on the screen and the gestures they make.
+#[derive(Clone, Debug, Default)]
+pub(super) struct Touches {
+    pub(super) settings: GestureSettings,
+    points: Vec<TouchPoint>,
+    down: HashSet<Gesture>,
+    pressed: HashSet<Gesture>,
+    released: HashSet<Gesture>,
+    /// Drag motion since the last end_frame, last position minus current.
+    drag: (f32, f32),
+    /// Change in the spread of two fingers since the last end_frame.
+    pinch: f32,
+    /// Spread of two fingers when the second went down.
+    pinch_start: f32,
+    /// Time and place of the last tap, for spotting a double tap.
+    last_tap: Option<(f32, (f32, f32))>,
+}
+
+impl Touches {
+    pub fn points(&self) -> &[TouchPoint] {
+        &self.points
+    }
+
+    pub fn is_down(&self, gesture: Gesture) -> bool {
+        self.down.contains(&gesture)
+    }
+
+    pub fn is_pressed(&self, gesture: Gesture) -> bool {
+        self.pressed.contains(&gesture)
+    }
+
+    pub fn is_released(&self, gesture: Gesture) -> bool {
+        self.released.contains(&gesture)
+    }
+
+    pub fn drag(&self) -> (f32, f32) {
+        self.drag
+    }
+
+    pub fn pinch(&self) -> f32 {
+        self.pinch
+    }
+
+    /// Updates the finger id, returning true if it tapped.
+    pub fn apply(
+        &mut self,
+        id: u64,
+        phase: TouchPhase,
+        position: (f32, f32),
+        seconds: f32,
+    ) -> bool {
+        match phase {
+            TouchPhase::Started => {
+                let crowded = !self.points.is_empty();
+                for point in &mut self.points {
+                    point.moved = true;
+                }
+
+                self.points.push(TouchPoint {
+                    id,
+                    position,
+                    start_position: position,
+                    start_time: seconds,
+                    moved: crowded,
+                });
+
+                if self.points.len() == 2 {
+                    self.pinch_start = self.spread();
+                }
+                false
+            }
+            TouchPhase::Moved => {
+                let Some(index) = self.points.iter().position(|p| p.id == id)
+                else {
+                    return false;
+                };
+                let before = self.spread();
+                let last = self.points[index].position;
+                self.points[index].position = position;
+
+                match self.points.len() {
+                    1 => {
+                        let point = &mut self.points[0];
+                        if !point.moved
+                            && distance(point.start_position, position)
+                                > self.settings.move_tolerance
+                        {
+                            point.moved = true;
+                            self.press(Gesture::Drag);
+                        }
+
+                        if self.is_down(Gesture::Drag) {
+                            self.drag.0 += last.0 - position.0;
+                            self.drag.1 += last.1 - position.1;
+                        }
+                    }
+                    2 => {
+                        let spread = self.spread();
+                        self.pinch += spread - before;
+
+                        if (spread - self.pinch_start).abs()
+                            > self.settings.move_tolerance
+                        {
+                            self.press(Gesture::Pinch);
+                        }
+                    }
+                    _ => {}
+                }
+                false
+            }
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                let Some(index) = self.points.iter().position(|p| p.id == id)
+                else {
+                    return false;
+                };
+                let point = self.points.remove(index);
+
+                if self.points.len() < 2 {
+                    self.release(Gesture::Pinch);
+                }
+
+                let long_pressed = self.is_down(Gesture::LongPress);
+                if self.points.is_empty() {
+                    self.release(Gesture::Drag);
+                    self.release(Gesture::LongPress);
+                }
+
+                let tapped = phase == TouchPhase::Ended
+                    && self.points.is_empty()
+                    && !point.moved
+                    && !long_pressed
+                    && seconds - point.start_time <= self.settings.tap_time;
+                if !tapped {
+                    return false;
+                }
+
+                self.tap(Gesture::Tap);
+                match self.last_tap.take() {
+                    Some((time, place))
+                        if seconds - time <= self.settings.double_tap_time
+                            && distance(place, point.position)
+                                <= self.settings.move_tolerance =>
+                    {
+                        self.tap(Gesture::DoubleTap);
+                    }
+                    _ => self.last_tap = Some((seconds, point.position)),
+                }
+
+                true
+            }
+        }
+    }
+
+    /// Turns a finger held still long enough into a long press.
+    pub fn update_time(&mut self, seconds: f32) {
+        if let [point] = self.points.as_slice() {
+            if !point.moved
+                && seconds - point.start_time >= self.settings.long_press_time
+            {
+                self.press(Gesture::LongPress);
+            }
+        }
+    }
+
+    /// Lifts every finger without any of them tapping.
+    pub fn cancel(&mut self) {
+        self.points.clear();
+        self.last_tap = None;
+        self.released.extend(self.down.drain());
+    }
+
+    pub fn end_frame(&mut self) {
+        self.pressed.clear();
+        self.released.clear();
+        self.drag = (0.0, 0.0);
+        self.pinch = 0.0;
+    }
+
+    fn press(&mut self, gesture: Gesture) {
+        if self.down.insert(gesture) {
+            self.pressed.insert(gesture);
+        }
+    }
+
+    fn release(&mut self, gesture: Gesture) {
+        if self.down.remove(&gesture) {
+            self.released.insert(gesture);
+        }
+    }
+
+    /// Pressed and released within the frame.
+    fn tap(&mut self, gesture: Gesture) {
+        self.pressed.insert(gesture);
+        self.released.insert(gesture);
+    }
+
+    /// Distance between the first two fingers.
+    fn spread(&self) -> f32 {
+        match self.points.as_slice() {
+            [first, second, ..] => distance(first.position, second.position),
+            _ => 0.0,
+        }
+    }
+}
+
+fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn touch(
+        touches: &mut Touches,
+        phase: TouchPhase,
+        at: (f32, f32),
+        seconds: f32,
+    ) -> bool {
+        touches.apply(0, phase, at, seconds)
+    }
+
+    #[test]
+    fn quick_touches_tap_and_double_tap() {
+        let mut touches = Touches::default();
+
+        touch(&mut touches, TouchPhase::Started, (10.0, 10.0), 0.0);
+        assert!(touch(&mut touches, TouchPhase::Ended, (10.0, 10.0), 0.1));
+        assert!(touches.is_pressed(Gesture::Tap));
+        assert!(touches.is_released(Gesture::Tap));
+        assert!(!touches.is_down(Gesture::Tap));
+        assert!(!touches.is_pressed(Gesture::DoubleTap));
+        touches.end_frame();
+
+        touch(&mut touches, TouchPhase::Started, (14.0, 12.0), 0.3);
+        assert!(touch(&mut touches, TouchPhase::Ended, (14.0, 12.0), 0.35));
+        assert!(touches.is_pressed(Gesture::Tap));
+        assert!(touches.is_pressed(Gesture::DoubleTap));
+        touches.end_frame();
+
+        // A third tap starts over instead of making another double tap.
+        touch(&mut touches, TouchPhase::Started, (14.0, 12.0), 0.5);
+        assert!(touch(&mut touches, TouchPhase::Ended, (14.0, 12.0), 0.55));
+        assert!(!touches.is_pressed(Gesture::DoubleTap));
+    }
+
+    #[test]
+    fn a_slow_touch_is_not_a_tap() {
+        let mut touches = Touches::default();
+
+        touch(&mut touches, TouchPhase::Started, (10.0, 10.0), 0.0);
+        assert!(!touch(&mut touches, TouchPhase::Ended, (10.0, 10.0), 0.3));
+        assert!(!touches.is_pressed(Gesture::Tap));
+    }
+
+    #[test]
+    fn holding_still_becomes_a_long_press() {
+        let mut touches = Touches::default();
+
+        touch(&mut touches, TouchPhase::Started, (10.0, 10.0), 0.0);
+        touches.update_time(0.4);
+        assert!(!touches.is_down(Gesture::LongPress));
+
+        touches.update_time(0.5);
+        assert!(touches.is_pressed(Gesture::LongPress));
+        touches.end_frame();
+
+        touches.update_time(0.6);
+        assert!(touches.is_down(Gesture::LongPress));
+        assert!(!touches.is_pressed(Gesture::LongPress));
+
+        assert!(!touch(&mut touches, TouchPhase::Ended, (10.0, 10.0), 0.7));
+        assert!(touches.is_released(Gesture::LongPress));
+        assert!(!touches.is_pressed(Gesture::Tap));
+    }
+
+    #[test]
+    fn moving_past_the_tolerance_drags_instead_of_tapping() {
+        let mut touches = Touches::default();
+
+        touch(&mut touches, TouchPhase::Started, (10.0, 10.0), 0.0);
+        touch(&mut touches, TouchPhase::Moved, (18.0, 10.0), 0.02);
+        assert!(!touches.is_down(Gesture::Drag));
+
+        touch(&mut touches, TouchPhase::Moved, (30.0, 10.0), 0.04);
+        assert!(touches.is_pressed(Gesture::Drag));
+        touches.end_frame();
+
+        touch(&mut touches, TouchPhase::Moved, (40.0, 5.0), 0.06);
+        assert_eq!(touches.drag(), (-10.0, 5.0));
+
+        // Quick enough to be a tap, but it moved.
+        assert!(!touch(&mut touches, TouchPhase::Ended, (40.0, 5.0), 0.08));
+        assert!(touches.is_released(Gesture::Drag));
+        assert!(!touches.is_pressed(Gesture::Tap));
+        touches.update_time(1.0);
+        assert!(!touches.is_down(Gesture::LongPress));
+    }
+
+    #[test]
+    fn two_fingers_spreading_pinch() {
+        let mut touches = Touches::default();
+
+        touches.apply(0, TouchPhase::Started, (0.0, 0.0), 0.0);
+        touches.apply(1, TouchPhase::Started, (10.0, 0.0), 0.01);
+        touches.apply(1, TouchPhase::Moved, (20.0, 0.0), 0.02);
+        assert!(!touches.is_down(Gesture::Pinch));
+        assert_eq!(touches.pinch(), 10.0);
+
+        touches.apply(1, TouchPhase::Moved, (30.0, 0.0), 0.03);
+        assert!(touches.is_pressed(Gesture::Pinch));
+        assert_eq!(touches.pinch(), 20.0);
+        touches.end_frame();
+
+        touches.apply(0, TouchPhase::Moved, (5.0, 0.0), 0.04);
+        assert_eq!(touches.pinch(), -5.0);
+
+        assert!(!touches.apply(1, TouchPhase::Ended, (30.0, 0.0), 0.05));
+        assert!(touches.is_released(Gesture::Pinch));
+        // The finger left behind shared the screen so it can not tap.
+        assert!(!touches.apply(0, TouchPhase::Ended, (5.0, 0.0), 0.06));
+        assert!(!touches.is_pressed(Gesture::Tap));
+    }
+}